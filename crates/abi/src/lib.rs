@@ -2,19 +2,44 @@ use alloy::sol;
 
 sol! {
     #[sol(rpc)]
+    #[allow(clippy::too_many_arguments)]
     FourMemeContract,
     "src/fourmeme_abi.json"
 }
 
+sol! {
+    #[sol(rpc)]
+    FourMemeHelper,
+    "src/fourmeme_helper_abi.json"
+}
+
 sol! {
     #[sol(rpc)]
     interface IERC20 {
+        function name() external view returns (string);
+        function symbol() external view returns (string);
+        function decimals() external view returns (uint8);
+        function totalSupply() external view returns (uint256);
         function balanceOf(address) external view returns (uint256);
         function approve(address spender, uint256 allowance) external;
         function allowance(address, address) external view returns (uint256);
     }
 }
 
+sol! {
+    #[sol(rpc)]
+    interface AggregatorV3Interface {
+        function decimals() external view returns (uint8);
+        function latestRoundData() external view returns (
+            uint80 roundId,
+            int256 answer,
+            uint256 startedAt,
+            uint256 updatedAt,
+            uint80 answeredInRound
+        );
+    }
+}
+
 sol! {
     function swapExactETHForTokens(
         uint256 amountOutMin,
@@ -0,0 +1,181 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use futures::future::join_all;
+use rpc::tracker::TxSubmitter;
+use sender::SignedTransaction;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::{error::BloxrouteError, relay::PrivateRelay};
+
+/// Outcome of one relay submission
+#[derive(Debug)]
+pub struct RelayReport {
+    pub relay: String,
+    pub latency: Duration,
+    pub result: Result<String, Error>,
+}
+
+/// Fans one signed transaction out to every configured relay concurrently
+#[derive(Clone, Default)]
+pub struct Broadcaster {
+    relays: Vec<Arc<dyn PrivateRelay>>,
+}
+
+impl Broadcaster {
+    pub fn new(relays: Vec<Arc<dyn PrivateRelay>>) -> Self {
+        Self { relays }
+    }
+
+    pub fn add_relay(&mut self, relay: Arc<dyn PrivateRelay>) {
+        self.relays.push(relay);
+    }
+
+    /// Submit to all relays and wait for every one of them
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<RelayReport>` - Per-relay latency and result, in relay order
+    pub async fn broadcast(&self, tx: &str) -> Vec<RelayReport> {
+        join_all(self.relays.iter().map(|relay| async move {
+            let started = Instant::now();
+            let result = relay.send_private_tx(tx).await;
+            RelayReport {
+                relay: relay.name().to_string(),
+                latency: started.elapsed(),
+                result,
+            }
+        }))
+        .await
+    }
+
+    /// Submit to all relays and return as soon as one accepts the transaction
+    ///
+    /// The remaining submissions keep running in the background so every relay still
+    /// receives the transaction.
+    ///
+    /// # Returns
+    ///
+    /// * `RelayReport` - The first successful report, or the last failure if all failed
+    pub async fn broadcast_first_success(&self, tx: &str) -> Result<RelayReport, BloxrouteError> {
+        if self.relays.is_empty() {
+            return Err(BloxrouteError::Broadcast(String::from(
+                "No relay configured",
+            )));
+        }
+
+        let (report_tx, mut report_rx) = tokio::sync::mpsc::unbounded_channel();
+        for relay in &self.relays {
+            let relay = Arc::clone(relay);
+            let tx = tx.to_string();
+            let report_tx = report_tx.clone();
+            tokio::spawn(async move {
+                let started = Instant::now();
+                let result = relay.send_private_tx(&tx).await;
+                let _ = report_tx.send(RelayReport {
+                    relay: relay.name().to_string(),
+                    latency: started.elapsed(),
+                    result,
+                });
+            });
+        }
+        drop(report_tx);
+
+        let mut last_failure = None;
+        while let Some(report) = report_rx.recv().await {
+            if report.result.is_ok() {
+                return Ok(report);
+            }
+            last_failure = Some(report);
+        }
+
+        // A relay task that panicked never reports
+        let Some(report) = last_failure else {
+            return Err(BloxrouteError::Broadcast(String::from(
+                "No relay reported a result",
+            )));
+        };
+        Err(BloxrouteError::Broadcast(format!(
+            "All relays failed, last error from {}: {:?}",
+            report.relay, report.result
+        )))
+    }
+}
+
+/// Lets the transaction tracker send replacements of private transactions to every relay
+#[async_trait]
+impl TxSubmitter for Broadcaster {
+    fn is_private(&self) -> bool {
+        true
+    }
+
+    async fn submit_signed(&self, signed: &SignedTransaction) -> Result<(), Error> {
+        self.broadcast_first_success(&signed.to_hex()).await?;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_broadcast() {
+    use crate::relay::{JsonRpcRelay, RelayMethod};
+    use serde_json::json;
+
+    let ok_url =
+        crate::mock::serve(|_| json!({ "jsonrpc": "2.0", "id": 1, "result": "0xbeef" })).await;
+    let err_url = crate::mock::serve(|_| {
+        json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32000, "message": "rejected" } })
+    })
+    .await;
+
+    let broadcaster = Broadcaster::new(vec![
+        Arc::new(JsonRpcRelay::new(
+            "failing",
+            err_url,
+            RelayMethod::SendPrivateTransaction,
+        )),
+        Arc::new(JsonRpcRelay::new(
+            "working",
+            ok_url,
+            RelayMethod::SendBundle,
+        )),
+    ]);
+
+    let reports = broadcaster.broadcast("0xdead").await;
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].relay, "failing");
+    assert!(reports[0].result.is_err());
+    assert_eq!(reports[1].result.as_ref().unwrap(), "0xbeef");
+
+    let first = broadcaster.broadcast_first_success("0xdead").await.unwrap();
+    assert_eq!(first.relay, "working");
+}
+
+#[tokio::test]
+async fn test_broadcast_without_reports() {
+    use crate::relay::PrivateRelay;
+
+    struct PanickingRelay;
+
+    #[async_trait]
+    impl PrivateRelay for PanickingRelay {
+        fn name(&self) -> &str {
+            "panicking"
+        }
+
+        async fn send_private_tx(&self, _tx: &str) -> Result<String, Error> {
+            panic!("relay task panicked")
+        }
+    }
+
+    let empty = Broadcaster::default();
+    assert!(matches!(
+        empty.broadcast_first_success("0xdead").await,
+        Err(BloxrouteError::Broadcast(_))
+    ));
+
+    let panicking = Broadcaster::new(vec![Arc::new(PanickingRelay)]);
+    assert!(matches!(
+        panicking.broadcast_first_success("0xdead").await,
+        Err(BloxrouteError::Broadcast(_))
+    ));
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+/// BSC mainnet network name used by Bloxroute
+pub const BSC_MAINNET: &str = "BSC-Mainnet";
+
+/// `blxr_submit_bundle` parameters
+///
+/// Transactions are executed in order and atomically: unless listed in
+/// `reverting_hashes`, a reverting transaction drops the whole bundle.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct BundleRequest {
+    /// Signed transactions hex strings without 0x prefix, in execution order
+    pub transaction: Vec<String>,
+    pub blockchain_network: String,
+    /// Target block number as 0x-prefixed hex
+    pub block_number: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_timestamp: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_timestamp: Option<u64>,
+    /// Hashes of transactions allowed to revert without dropping the bundle
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reverting_hashes: Vec<String>,
+    pub mev_builders: Vec<String>,
+}
+
+impl BundleRequest {
+    /// Create an empty bundle targeting `block_number`, sent to all builders
+    pub fn new(block_number: u64) -> Self {
+        Self {
+            transaction: Vec::new(),
+            blockchain_network: BSC_MAINNET.to_string(),
+            block_number: format!("0x{:x}", block_number),
+            min_timestamp: None,
+            max_timestamp: None,
+            reverting_hashes: Vec::new(),
+            mev_builders: vec!["all".to_string()],
+        }
+    }
+
+    /// Append a signed transaction (with or without 0x prefix)
+    pub fn push_tx(mut self, tx: impl AsRef<str>) -> Self {
+        let tx = tx.as_ref();
+        let tx_hex = tx
+            .strip_prefix("0x")
+            .or_else(|| tx.strip_prefix("0X"))
+            .unwrap_or(tx);
+        self.transaction.push(tx_hex.to_string());
+        self
+    }
+
+    /// Allow the transaction with `tx_hash` to revert
+    pub fn allow_revert(mut self, tx_hash: impl Into<String>) -> Self {
+        self.reverting_hashes.push(tx_hash.into());
+        self
+    }
+
+    /// Restrict the bundle to the given builders
+    pub fn builders(mut self, builders: Vec<String>) -> Self {
+        self.mev_builders = builders;
+        self
+    }
+
+    /// Only include the bundle within this timestamp window
+    pub fn timestamps(mut self, min_timestamp: Option<u64>, max_timestamp: Option<u64>) -> Self {
+        self.min_timestamp = min_timestamp;
+        self.max_timestamp = max_timestamp;
+        self
+    }
+}
+
+/// `blxr_submit_bundle` result
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct BundleResponse {
+    #[serde(rename = "bundleHash")]
+    pub bundle_hash: String,
+}
+
+/// JSON-RPC 2.0 request envelope
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonRpcRequest<'a, P> {
+    pub id: &'a str,
+    pub jsonrpc: &'a str,
+    pub method: &'a str,
+    pub params: P,
+}
+
+impl<'a, P> JsonRpcRequest<'a, P> {
+    pub fn new(method: &'a str, params: P) -> Self {
+        Self {
+            id: "1",
+            jsonrpc: "2.0",
+            method,
+            params,
+        }
+    }
+}
+
+/// JSON-RPC 2.0 error object
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+/// JSON-RPC 2.0 response envelope
+#[derive(Debug, Clone, Deserialize)]
+pub struct JsonRpcResponse<R> {
+    pub result: Option<R>,
+    pub error: Option<JsonRpcError>,
+}
+
+#[test]
+fn test_bundle_request() {
+    let bundle = BundleRequest::new(42_000_000)
+        .push_tx("0xaa")
+        .push_tx("bb")
+        .allow_revert("0x01");
+    let value = serde_json::to_value(&bundle).unwrap();
+
+    assert_eq!(value["transaction"], serde_json::json!(["aa", "bb"]));
+    assert_eq!(value["block_number"], "0x280de80");
+    assert_eq!(value["reverting_hashes"], serde_json::json!(["0x01"]));
+    assert_eq!(value["mev_builders"], serde_json::json!(["all"]));
+    assert!(value.get("min_timestamp").is_none());
+}
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+/// Bloxroute Cloud-API endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Region {
+    /// Geo-routed global endpoint
+    Global,
+    Virginia,
+    London,
+    Frankfurt,
+    Singapore,
+    Tokyo,
+    /// Any other endpoint, e.g. a self-hosted gateway
+    Custom(String),
+}
+
+impl Region {
+    /// HTTP endpoint of the region
+    pub fn endpoint(&self) -> String {
+        match self {
+            Region::Global => String::from("https://api.blxrbdn.com"),
+            Region::Virginia => String::from("https://virginia.bsc.blxrbdn.com"),
+            Region::London => String::from("https://uk.bsc.blxrbdn.com"),
+            Region::Frankfurt => String::from("https://germany.bsc.blxrbdn.com"),
+            Region::Singapore => String::from("https://singapore.bsc.blxrbdn.com"),
+            Region::Tokyo => String::from("https://tokyo.bsc.blxrbdn.com"),
+            Region::Custom(url) => url.clone(),
+        }
+    }
+
+    /// Websocket endpoint of the region, used for streams
+    pub fn ws_endpoint(&self) -> String {
+        match self {
+            Region::Custom(url) => url.clone(),
+            region => format!("{}/ws", region.endpoint().replacen("https://", "wss://", 1)),
+        }
+    }
+}
+
+/// Connection settings of the Bloxroute client
+#[derive(Debug, Clone)]
+pub struct BloxrouteConfig {
+    pub region: Region,
+    /// Whole request timeout
+    pub request_timeout: Duration,
+    pub connect_timeout: Duration,
+    /// TCP keep-alive interval of pooled connections
+    pub tcp_keepalive: Duration,
+    /// How long idle connections stay in the pool
+    pub pool_idle_timeout: Duration,
+    /// HTTP/2 keep-alive ping interval, `None` to disable
+    pub http2_keep_alive_interval: Option<Duration>,
+    /// Open the connection at construction time with a `HEAD` request
+    pub warm_up: bool,
+}
+
+impl Default for BloxrouteConfig {
+    fn default() -> Self {
+        Self {
+            region: Region::Global,
+            request_timeout: Duration::from_secs(3),
+            connect_timeout: Duration::from_secs(2),
+            tcp_keepalive: Duration::from_secs(30),
+            pool_idle_timeout: Duration::from_secs(300),
+            http2_keep_alive_interval: Some(Duration::from_secs(15)),
+            warm_up: true,
+        }
+    }
+}
+
+impl BloxrouteConfig {
+    pub fn with_region(mut self, region: Region) -> Self {
+        self.region = region;
+        self
+    }
+
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    pub fn with_warm_up(mut self, warm_up: bool) -> Self {
+        self.warm_up = warm_up;
+        self
+    }
+}
@@ -0,0 +1,67 @@
+use crate::bundle::JsonRpcError;
+
+/// Errors returned by the Bloxroute client
+#[derive(Debug, thiserror::Error)]
+pub enum BloxrouteError {
+    /// The API key is missing, invalid or lacks the required tier
+    #[error("Bloxroute authorization failed: {0}")]
+    Unauthorized(String),
+
+    /// Too many requests for the account tier
+    #[error("Bloxroute rate limit exceeded: {0}")]
+    RateLimited(String),
+
+    /// The transaction or bundle was rejected
+    #[error("Bloxroute rejected the request: {code} {message}")]
+    Rejected { code: i64, message: String },
+
+    /// Connection, timeout or body decoding failure
+    #[error("Bloxroute transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    /// Websocket stream failure
+    #[error("Bloxroute websocket error: {0}")]
+    WebSocket(Box<tokio_tungstenite::tungstenite::Error>),
+
+    /// The response could not be understood
+    #[error("Bloxroute returned an invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// No relay of a broadcast accepted the transaction
+    #[error("Broadcast failed: {0}")]
+    Broadcast(String),
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for BloxrouteError {
+    fn from(error: tokio_tungstenite::tungstenite::Error) -> Self {
+        BloxrouteError::WebSocket(Box::new(error))
+    }
+}
+
+impl BloxrouteError {
+    /// Classify a JSON-RPC error object
+    pub(crate) fn from_rpc(error: JsonRpcError) -> Self {
+        let message = error.message.to_lowercase();
+        if message.contains("auth") || (message.contains("account") && message.contains("tier")) {
+            BloxrouteError::Unauthorized(error.message)
+        } else if message.contains("rate limit") || message.contains("too many") {
+            BloxrouteError::RateLimited(error.message)
+        } else {
+            BloxrouteError::Rejected {
+                code: error.code,
+                message: error.message,
+            }
+        }
+    }
+
+    /// Whether retrying the same request later may succeed
+    #[inline]
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            BloxrouteError::RateLimited(_)
+                | BloxrouteError::Transport(_)
+                | BloxrouteError::WebSocket(_)
+        )
+    }
+}
@@ -1,6 +1,29 @@
-use anyhow::{Error, Result};
-use reqwest::Client;
-use serde_json::{Value, json};
+pub mod broadcast;
+pub mod bundle;
+pub mod config;
+pub mod error;
+#[cfg(test)]
+mod mock;
+pub mod relay;
+pub mod stream;
+
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::json;
+
+use crate::{
+    bundle::{BundleRequest, BundleResponse, JsonRpcRequest, JsonRpcResponse},
+    config::BloxrouteConfig,
+    error::BloxrouteError,
+};
+
+/// `bsc_private_tx` result
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+pub struct BloxrouteResponse {
+    #[serde(rename = "txHash")]
+    pub tx_hash: String,
+}
+
 pub struct Bloxroute {
     client: Client,
     api_key: String,
@@ -8,9 +31,12 @@ pub struct Bloxroute {
 }
 
 impl Bloxroute {
+    /// Create a client with the default configuration, without warm-up
     pub fn init(api_key: String) -> Self {
-        let url = String::from("https://api.blxrbdn.com");
-        let client = Client::new();
+        let config = BloxrouteConfig::default();
+        let url = config.region.endpoint();
+        let client =
+            Self::build_client(&config).expect("Failed to build the Bloxroute HTTP client");
 
         Self {
             client,
@@ -19,7 +45,58 @@ impl Bloxroute {
         }
     }
 
-    pub async fn send_private_tx(&self, tx: String) -> Result<Value, Error> {
+    /// Use another endpoint, e.g. a regional gateway
+    ///
+    /// Same as `with_config` with `Region::Custom(url)` and warm-up disabled.
+    #[inline]
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Create a client from `config`, warming the connection up if enabled
+    pub async fn with_config(
+        api_key: String,
+        config: BloxrouteConfig,
+    ) -> Result<Self, BloxrouteError> {
+        let bloxroute = Self {
+            client: Self::build_client(&config)?,
+            api_key,
+            url: config.region.endpoint(),
+        };
+
+        if config.warm_up {
+            bloxroute.warm_up().await?;
+        }
+
+        Ok(bloxroute)
+    }
+
+    pub(crate) fn build_client(config: &BloxrouteConfig) -> Result<Client, BloxrouteError> {
+        let mut builder = Client::builder()
+            .timeout(config.request_timeout)
+            .connect_timeout(config.connect_timeout)
+            .tcp_keepalive(config.tcp_keepalive)
+            .tcp_nodelay(true)
+            .pool_idle_timeout(config.pool_idle_timeout);
+        if let Some(interval) = config.http2_keep_alive_interval {
+            builder = builder
+                .http2_keep_alive_interval(interval)
+                .http2_keep_alive_while_idle(true);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// Open a pooled connection ahead of the first submission
+    ///
+    /// Sends a `HEAD` request: only the TLS handshake matters, so the status and body of
+    /// the response are ignored and only connection errors are returned.
+    pub async fn warm_up(&self) -> Result<(), BloxrouteError> {
+        let _ignored = self.client.head(self.url.clone()).send().await?;
+        Ok(())
+    }
+
+    pub async fn send_private_tx(&self, tx: String) -> Result<BloxrouteResponse, BloxrouteError> {
         // Remove 0x prefix if present (documentation says transaction should be without 0x prefix)
         let tx_hex = if tx.starts_with("0x") || tx.starts_with("0X") {
             tx[2..].to_string()
@@ -27,35 +104,140 @@ impl Bloxroute {
             tx
         };
 
+        self.request(
+            "bsc_private_tx",
+            json!({
+                "transaction": tx_hex,
+                "mev_builders": ["all"]
+            }),
+        )
+        .await
+    }
+
+    /// Submit an atomic bundle with `blxr_submit_bundle`
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle` - The ordered transactions, target block and revert allowance
+    ///
+    /// # Returns
+    ///
+    /// * `BundleResponse` - The bundle hash assigned by Bloxroute
+    pub async fn submit_bundle(
+        &self,
+        bundle: &BundleRequest,
+    ) -> Result<BundleResponse, BloxrouteError> {
+        self.request("blxr_submit_bundle", bundle).await
+    }
+
+    /// Send a JSON-RPC request and decode its result
+    async fn request<P: Serialize, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: P,
+    ) -> Result<R, BloxrouteError> {
         let response = self
             .client
             .post(self.url.clone())
             .header("Content-Type", "application/json")
             .header("Authorization", self.api_key.clone())
-            .json(&json!({
-                "id": "1",
-                "jsonrpc": "2.0",
-                "method": "bsc_private_tx",
-                "params": {
-                    "transaction": tx_hex,
-                    "mev_builders": ["all"]
-                }
-            }))
+            .json(&JsonRpcRequest::new(method, params))
             .send()
             .await?;
 
-        let result: serde_json::Value = response.json().await?;
-
-        // Extract txHash from result object: result.result.txHash
-        if let Some(result_obj) = result.get("result") {
-            return Ok(result_obj.clone());
+        let status = response.status();
+        let body = response.text().await?;
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                return Err(BloxrouteError::Unauthorized(body));
+            }
+            StatusCode::TOO_MANY_REQUESTS => return Err(BloxrouteError::RateLimited(body)),
+            _ => {}
         }
 
-        // Log the full response for debugging
-        let error = result
-            .get("error")
-            .map(|e| e.to_string())
-            .unwrap_or_else(|| format!("Unknown error. Full response: {}", result));
-        Err(Error::msg(format!("BloxRoute API error: {}", error)))
+        let response: JsonRpcResponse<R> = serde_json::from_str(&body)
+            .map_err(|e| BloxrouteError::InvalidResponse(format!("{}: {}", e, body)))?;
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(BloxrouteError::from_rpc(error)),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(BloxrouteError::InvalidResponse(body)),
+        }
     }
 }
+
+#[tokio::test]
+async fn test_submit_bundle() {
+    use crate::config::Region;
+
+    let url = crate::mock::serve(|request| {
+        assert_eq!(request["method"], "blxr_submit_bundle");
+        assert_eq!(request["params"]["transaction"], json!(["aa", "bb"]));
+        assert_eq!(request["params"]["block_number"], "0x64");
+        json!({ "jsonrpc": "2.0", "id": "1", "result": { "bundleHash": "0xfeed" } })
+    })
+    .await;
+
+    let config = BloxrouteConfig::default()
+        .with_region(Region::Custom(url))
+        .with_warm_up(false);
+    let bloxroute = Bloxroute::with_config(String::from("key"), config)
+        .await
+        .unwrap();
+    let bundle = BundleRequest::new(100).push_tx("0xaa").push_tx("0xbb");
+    let response = bloxroute.submit_bundle(&bundle).await.unwrap();
+    assert_eq!(response.bundle_hash, "0xfeed");
+}
+
+#[tokio::test]
+async fn test_send_private_tx_errors() {
+    use crate::config::Region;
+
+    let url = crate::mock::serve(|request| match request["params"]["transaction"].as_str() {
+        Some("aa") => json!({ "jsonrpc": "2.0", "id": "1", "result": { "txHash": "0xbeef" } }),
+        Some("bb") => json!({
+            "jsonrpc": "2.0", "id": "1",
+            "error": { "code": -32004, "message": "Authorization failed: invalid account id" }
+        }),
+        _ => json!({
+            "jsonrpc": "2.0", "id": "1",
+            "error": { "code": -32602, "message": "transaction underpriced" }
+        }),
+    })
+    .await;
+    let config = BloxrouteConfig::default()
+        .with_region(Region::Custom(url))
+        .with_warm_up(false);
+    let bloxroute = Bloxroute::with_config(String::from("key"), config)
+        .await
+        .unwrap();
+
+    let response = bloxroute
+        .send_private_tx(String::from("0xaa"))
+        .await
+        .unwrap();
+    assert_eq!(response.tx_hash, "0xbeef");
+    assert!(matches!(
+        bloxroute.send_private_tx(String::from("bb")).await,
+        Err(BloxrouteError::Unauthorized(_))
+    ));
+    assert!(matches!(
+        bloxroute.send_private_tx(String::from("cc")).await,
+        Err(BloxrouteError::Rejected { code: -32602, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_warm_up() {
+    use crate::config::Region;
+
+    let url = crate::mock::serve(|_| json!({})).await;
+    let config = BloxrouteConfig::default().with_region(Region::Custom(url.clone()));
+    let bloxroute = Bloxroute::with_config(String::from("key"), config)
+        .await
+        .unwrap();
+    assert_eq!(bloxroute.url, url);
+
+    let bloxroute = Bloxroute::init(String::from("key")).with_url(url.clone());
+    assert_eq!(bloxroute.url, url);
+    bloxroute.warm_up().await.unwrap();
+}
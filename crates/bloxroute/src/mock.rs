@@ -0,0 +1,112 @@
+//! Minimal JSON-RPC HTTP server for tests
+
+use serde_json::Value;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+/// Serve every POST with `handler(request_body)` and return the server URL
+pub(crate) async fn serve<F>(handler: F) -> String
+where
+    F: Fn(Value) -> Value + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handler = std::sync::Arc::new(handler);
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let handler = handler.clone();
+            tokio::spawn(async move {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                loop {
+                    let n = stream.read(&mut chunk).await.unwrap_or(0);
+                    if n == 0 {
+                        return;
+                    }
+                    buf.extend_from_slice(&chunk[..n]);
+                    let Some(header_end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else {
+                        continue;
+                    };
+                    let headers = String::from_utf8_lossy(&buf[..header_end]).to_lowercase();
+                    let content_length = headers
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length:"))
+                        .and_then(|len| len.trim().parse::<usize>().ok())
+                        .unwrap_or(0);
+                    let body_start = header_end + 4;
+                    if buf.len() < body_start + content_length {
+                        continue;
+                    }
+
+                    let request: Value =
+                        serde_json::from_slice(&buf[body_start..body_start + content_length])
+                            .unwrap_or(Value::Null);
+                    let body = handler(request).to_string();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    return;
+                }
+            });
+        }
+    });
+
+    url
+}
+
+/// Websocket server answering every `subscribe` with `sub-<n>`, then pushing
+/// `notifications` once all subscriptions are made and closing
+///
+/// `before_ack` frames are pushed ahead of the first subscribe response.
+pub(crate) async fn serve_ws(before_ack: Vec<String>, notifications: Vec<Value>) -> String {
+    use futures::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let expected_subscriptions = notifications.len();
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+
+        for n in 1..=expected_subscriptions {
+            let Some(Ok(Message::Text(text))) = socket.next().await else {
+                return;
+            };
+            let request: Value = serde_json::from_str(&text).unwrap();
+            if n == 1 {
+                for frame in &before_ack {
+                    socket
+                        .send(Message::Text(frame.clone().into()))
+                        .await
+                        .unwrap();
+                }
+            }
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": format!("sub-{}", n),
+            });
+            socket
+                .send(Message::Text(response.to_string().into()))
+                .await
+                .unwrap();
+        }
+        for notification in notifications {
+            socket
+                .send(Message::Text(notification.to_string().into()))
+                .await
+                .unwrap();
+        }
+        let _ = socket.close(None).await;
+    });
+
+    url
+}
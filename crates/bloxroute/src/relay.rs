@@ -0,0 +1,185 @@
+use anyhow::{Error, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use rpc::tracker::TxSubmitter;
+use sender::SignedTransaction;
+use serde_json::{Value, json};
+
+use crate::{Bloxroute, config::BloxrouteConfig, error::BloxrouteError};
+
+/// A private transaction relay (builder, validator or MEV gateway)
+#[async_trait]
+pub trait PrivateRelay: Send + Sync {
+    /// Name used in reports
+    fn name(&self) -> &str;
+
+    /// Submit a signed transaction hex string (with or without 0x prefix)
+    ///
+    /// # Returns
+    ///
+    /// * `String` - The relay acknowledgement, usually the transaction hash
+    async fn send_private_tx(&self, tx: &str) -> Result<String, Error>;
+}
+
+#[async_trait]
+impl PrivateRelay for Bloxroute {
+    fn name(&self) -> &str {
+        "bloxroute"
+    }
+
+    async fn send_private_tx(&self, tx: &str) -> Result<String, Error> {
+        let response = Bloxroute::send_private_tx(self, tx.to_string()).await?;
+        Ok(response.tx_hash)
+    }
+}
+
+/// Lets the transaction tracker send replacements of private transactions through Bloxroute
+#[async_trait]
+impl TxSubmitter for Bloxroute {
+    fn is_private(&self) -> bool {
+        true
+    }
+
+    async fn submit_signed(&self, signed: &SignedTransaction) -> Result<(), Error> {
+        Bloxroute::send_private_tx(self, signed.to_hex()).await?;
+        Ok(())
+    }
+}
+
+/// JSON-RPC method used to submit to a generic relay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelayMethod {
+    /// `eth_sendRawTransaction`, e.g. 48Club puissant
+    SendRawTransaction,
+    /// `eth_sendPrivateTransaction` with `{ "tx": ... }`
+    SendPrivateTransaction,
+    /// `eth_sendBundle` with a single-transaction bundle
+    SendBundle,
+}
+
+impl RelayMethod {
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelayMethod::SendRawTransaction => "eth_sendRawTransaction",
+            RelayMethod::SendPrivateTransaction => "eth_sendPrivateTransaction",
+            RelayMethod::SendBundle => "eth_sendBundle",
+        }
+    }
+
+    #[inline]
+    fn params(&self, tx_hex: &str) -> Value {
+        match self {
+            RelayMethod::SendRawTransaction => json!([tx_hex]),
+            RelayMethod::SendPrivateTransaction => json!([{ "tx": tx_hex }]),
+            RelayMethod::SendBundle => json!([{ "txs": [tx_hex] }]),
+        }
+    }
+}
+
+/// Relay speaking plain Ethereum JSON-RPC, configured by URL
+pub struct JsonRpcRelay {
+    name: String,
+    url: String,
+    method: RelayMethod,
+    client: Client,
+}
+
+impl JsonRpcRelay {
+    /// Create a relay with the timeouts of the default `BloxrouteConfig`
+    pub fn new(name: impl Into<String>, url: impl Into<String>, method: RelayMethod) -> Self {
+        Self::with_config(name, url, method, &BloxrouteConfig::default())
+            .expect("Failed to build the relay HTTP client")
+    }
+
+    /// Create a relay with the connect and request timeouts of `config`, its region is ignored
+    pub fn with_config(
+        name: impl Into<String>,
+        url: impl Into<String>,
+        method: RelayMethod,
+        config: &BloxrouteConfig,
+    ) -> Result<Self, BloxrouteError> {
+        Ok(Self {
+            name: name.into(),
+            url: url.into(),
+            method,
+            client: Bloxroute::build_client(config)?,
+        })
+    }
+}
+
+#[async_trait]
+impl PrivateRelay for JsonRpcRelay {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send_private_tx(&self, tx: &str) -> Result<String, Error> {
+        // JSON-RPC relays expect the 0x prefix
+        let tx_hex = if tx.starts_with("0x") || tx.starts_with("0X") {
+            tx.to_string()
+        } else {
+            format!("0x{}", tx)
+        };
+
+        let response: Value = self
+            .client
+            .post(&self.url)
+            .json(&json!({
+                "id": 1,
+                "jsonrpc": "2.0",
+                "method": self.method.as_str(),
+                "params": self.method.params(&tx_hex),
+            }))
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        match response.get("result") {
+            Some(Value::String(hash)) => Ok(hash.clone()),
+            Some(result) => Ok(result.to_string()),
+            None => {
+                let error = response
+                    .get("error")
+                    .map(|e| e.to_string())
+                    .unwrap_or_else(|| format!("Unknown error. Full response: {}", response));
+                Err(Error::msg(format!("{} relay error: {}", self.name, error)))
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_json_rpc_relay() {
+    let url = crate::mock::serve(|request| {
+        assert_eq!(request["method"], "eth_sendRawTransaction");
+        assert_eq!(request["params"][0], "0xdead");
+        json!({ "jsonrpc": "2.0", "id": 1, "result": "0xbeef" })
+    })
+    .await;
+
+    let relay = JsonRpcRelay::new("puissant", url, RelayMethod::SendRawTransaction);
+    assert_eq!(relay.send_private_tx("dead").await.unwrap(), "0xbeef");
+}
+
+#[tokio::test]
+async fn test_json_rpc_relay_timeout() {
+    use std::time::Duration;
+
+    // Accepts the connection but never answers
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let mut streams = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            streams.push(stream);
+        }
+    });
+
+    let config = BloxrouteConfig::default().with_request_timeout(Duration::from_millis(100));
+    let relay =
+        JsonRpcRelay::with_config("silent", url, RelayMethod::SendRawTransaction, &config).unwrap();
+    let result = tokio::time::timeout(Duration::from_secs(5), relay.send_private_tx("dead")).await;
+    assert!(result.expect("relay request outlived its timeout").is_err());
+}
@@ -0,0 +1,421 @@
+use alloy::{primitives::B256, rpc::types::Transaction};
+use futures::{SinkExt, Stream, StreamExt, stream};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::VecDeque;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{Message, client::IntoClientRequest, http::HeaderValue},
+};
+
+use crate::{
+    bundle::{BSC_MAINNET, JsonRpcError, JsonRpcResponse},
+    error::BloxrouteError,
+};
+
+/// Bloxroute Cloud-API stream names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamTopic {
+    /// Transactions first seen by the BDN
+    NewTxs,
+    /// Transactions validated by the gateway's node
+    PendingTxs,
+    /// Blocks accepted by the node
+    NewBlocks,
+    /// Blocks propagated through the BDN, before the node sees them
+    BdnBlocks,
+}
+
+impl StreamTopic {
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StreamTopic::NewTxs => "newTxs",
+            StreamTopic::PendingTxs => "pendingTxs",
+            StreamTopic::NewBlocks => "newBlocks",
+            StreamTopic::BdnBlocks => "bdnBlocks",
+        }
+    }
+
+    #[inline]
+    fn is_block(&self) -> bool {
+        matches!(self, StreamTopic::NewBlocks | StreamTopic::BdnBlocks)
+    }
+}
+
+/// Subscription options
+#[derive(Debug, Clone)]
+pub struct SubscribeOptions {
+    /// Fields to include in notifications
+    pub include: Vec<String>,
+    /// Filter expression, e.g. `{to} == '0x5c95...'`
+    pub filters: Option<String>,
+    pub blockchain_network: String,
+}
+
+impl SubscribeOptions {
+    /// Options for `topic` including every field needed to decode notifications
+    pub fn new(topic: StreamTopic) -> Self {
+        let include = if topic.is_block() {
+            vec!["hash", "header", "transactions"]
+        } else {
+            vec!["tx_hash", "tx_contents"]
+        };
+        Self {
+            include: include.into_iter().map(String::from).collect(),
+            filters: None,
+            blockchain_network: BSC_MAINNET.to_string(),
+        }
+    }
+
+    pub fn with_filters(mut self, filters: impl Into<String>) -> Self {
+        self.filters = Some(filters.into());
+        self
+    }
+}
+
+/// A block notification
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockNotification {
+    pub hash: B256,
+    /// Raw block header as sent by Bloxroute
+    #[serde(default)]
+    pub header: Value,
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+}
+
+impl BlockNotification {
+    /// Block number from the header
+    pub fn number(&self) -> Option<u64> {
+        let number = self.header.get("number")?.as_str()?;
+        u64::from_str_radix(number.trim_start_matches("0x"), 16).ok()
+    }
+}
+
+/// A decoded stream notification
+#[derive(Debug, Clone)]
+pub enum StreamEvent {
+    /// A transaction; the contents are absent when not included in the subscription
+    Transaction {
+        hash: B256,
+        tx: Option<Box<Transaction>>,
+    },
+    Block(Box<BlockNotification>),
+}
+
+/// A notification and the subscription it belongs to
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub subscription: String,
+    pub event: StreamEvent,
+}
+
+#[derive(Deserialize)]
+struct TxNotification {
+    #[serde(rename = "txHash")]
+    tx_hash: B256,
+    #[serde(rename = "txContents")]
+    tx_contents: Option<Transaction>,
+}
+
+#[derive(Deserialize)]
+struct NotificationParams {
+    subscription: String,
+    result: Value,
+}
+
+/// Websocket client for the Bloxroute Cloud-API streams
+pub struct BloxrouteStream {
+    socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    /// Whether each subscription id streams blocks
+    subscriptions: Vec<(String, bool)>,
+    /// Notifications, or frames that failed to decode, received while waiting for a
+    /// subscribe response
+    buffered: VecDeque<Result<Notification, BloxrouteError>>,
+    next_id: u64,
+}
+
+impl BloxrouteStream {
+    /// Connect to a websocket endpoint, e.g. `Region::Virginia.ws_endpoint()`
+    pub async fn connect(url: &str, api_key: &str) -> Result<Self, BloxrouteError> {
+        let mut request = url.into_client_request()?;
+        let authorization = HeaderValue::from_str(api_key)
+            .map_err(|e| BloxrouteError::Unauthorized(e.to_string()))?;
+        request.headers_mut().insert("Authorization", authorization);
+
+        let (socket, _) = connect_async(request).await?;
+        Ok(Self {
+            socket,
+            subscriptions: Vec::new(),
+            buffered: VecDeque::new(),
+            next_id: 1,
+        })
+    }
+
+    /// Subscribe to `topic` and return the subscription id
+    pub async fn subscribe(
+        &mut self,
+        topic: StreamTopic,
+        options: SubscribeOptions,
+    ) -> Result<String, BloxrouteError> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let mut params = json!({
+            "include": options.include,
+            "blockchain_network": options.blockchain_network,
+        });
+        if let Some(filters) = options.filters {
+            params["filters"] = Value::String(filters);
+        }
+        let request = json!({
+            "id": id,
+            "method": "subscribe",
+            "params": [topic.as_str(), params],
+        });
+        self.socket
+            .send(Message::Text(request.to_string().into()))
+            .await?;
+
+        loop {
+            let message = match self.read_json().await {
+                Ok(message) => message,
+                // An undecodable frame is handed to `next_notification`, like the receive loop does
+                Err(e @ BloxrouteError::InvalidResponse(_)) => {
+                    self.buffered.push_back(Err(e));
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            if message.get("id").and_then(Value::as_u64) == Some(id) {
+                let response: JsonRpcResponse<String> = serde_json::from_value(message.clone())
+                    .map_err(|e| BloxrouteError::InvalidResponse(format!("{}: {}", e, message)))?;
+                return match (response.result, response.error) {
+                    (_, Some(error)) => Err(BloxrouteError::from_rpc(error)),
+                    (Some(subscription), None) => {
+                        self.subscriptions
+                            .push((subscription.clone(), topic.is_block()));
+                        Ok(subscription)
+                    }
+                    (None, None) => Err(BloxrouteError::InvalidResponse(message.to_string())),
+                };
+            }
+
+            if let Some(notification) = self.decode(message).transpose() {
+                self.buffered.push_back(notification);
+            }
+        }
+    }
+
+    /// Wait for the next notification, `None` once the connection is closed
+    pub async fn next_notification(&mut self) -> Option<Result<Notification, BloxrouteError>> {
+        if let Some(notification) = self.buffered.pop_front() {
+            return Some(notification);
+        }
+
+        loop {
+            let message = match self.read_json().await {
+                Ok(message) => message,
+                Err(BloxrouteError::WebSocket(e))
+                    if matches!(
+                        *e,
+                        tokio_tungstenite::tungstenite::Error::ConnectionClosed
+                            | tokio_tungstenite::tungstenite::Error::AlreadyClosed
+                    ) =>
+                {
+                    return None;
+                }
+                Err(e) => return Some(Err(e)),
+            };
+            match self.decode(message) {
+                Ok(Some(notification)) => return Some(Ok(notification)),
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+
+    /// Turn the client into a stream of notifications
+    pub fn into_stream(self) -> impl Stream<Item = Result<Notification, BloxrouteError>> {
+        stream::unfold(self, |mut client| async move {
+            client
+                .next_notification()
+                .await
+                .map(|notification| (notification, client))
+        })
+    }
+
+    async fn read_json(&mut self) -> Result<Value, BloxrouteError> {
+        loop {
+            let Some(message) = self.socket.next().await else {
+                return Err(tokio_tungstenite::tungstenite::Error::ConnectionClosed.into());
+            };
+            match message? {
+                Message::Text(text) => {
+                    return serde_json::from_str(&text)
+                        .map_err(|e| BloxrouteError::InvalidResponse(format!("{}: {}", e, text)));
+                }
+                Message::Binary(bytes) => {
+                    return serde_json::from_slice(&bytes)
+                        .map_err(|e| BloxrouteError::InvalidResponse(e.to_string()));
+                }
+                Message::Close(_) => {
+                    return Err(tokio_tungstenite::tungstenite::Error::ConnectionClosed.into());
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    fn decode(&self, message: Value) -> Result<Option<Notification>, BloxrouteError> {
+        if let Some(error) = message.get("error") {
+            let error: JsonRpcError = serde_json::from_value(error.clone())
+                .map_err(|e| BloxrouteError::InvalidResponse(format!("{}: {}", e, message)))?;
+            return Err(BloxrouteError::from_rpc(error));
+        }
+        let Some(params) = message.get("params") else {
+            return Ok(None);
+        };
+        let params: NotificationParams = serde_json::from_value(params.clone())
+            .map_err(|e| BloxrouteError::InvalidResponse(format!("{}: {}", e, message)))?;
+
+        let is_block = self
+            .subscriptions
+            .iter()
+            .find(|(id, _)| *id == params.subscription)
+            .map(|(_, is_block)| *is_block)
+            .unwrap_or_else(|| params.result.get("txHash").is_none());
+
+        let event = if is_block {
+            let block: BlockNotification = serde_json::from_value(params.result)
+                .map_err(|e| BloxrouteError::InvalidResponse(e.to_string()))?;
+            StreamEvent::Block(Box::new(block))
+        } else {
+            let tx: TxNotification = serde_json::from_value(params.result)
+                .map_err(|e| BloxrouteError::InvalidResponse(e.to_string()))?;
+            StreamEvent::Transaction {
+                hash: tx.tx_hash,
+                tx: tx.tx_contents.map(Box::new),
+            }
+        };
+
+        Ok(Some(Notification {
+            subscription: params.subscription,
+            event,
+        }))
+    }
+}
+
+#[tokio::test]
+async fn test_stream_notifications() {
+    let tx = json!({
+        "hash": "0x1f0fd85f4fcc7eba0db0c1dbb1cc3c6a4c3fbee9a1dcb4ff5d2b47c6be9f8f4f",
+        "nonce": "0x1",
+        "from": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+        "to": "0x5c952063c7fc8610ffdb798152d69f0b9550762b",
+        "value": "0xde0b6b3a7640000",
+        "gasPrice": "0x3b9aca00",
+        "gas": "0x30d40",
+        "input": "0x",
+        "v": "0x93",
+        "r": "0x1b5e176d927f8e9ab405058b2d2457392da3e20f328b16ddabcebc33eaac5fea",
+        "s": "0x4ba69724e8f69de52f0125ad8b3c5c2cef33019bac3249e2c0a2192766d1721c",
+        "type": "0x0",
+        "chainId": "0x38"
+    });
+    let notifications = vec![
+        json!({
+            "jsonrpc": "2.0", "method": "subscribe",
+            "params": { "subscription": "sub-1", "result": {
+                "txHash": "0x1f0fd85f4fcc7eba0db0c1dbb1cc3c6a4c3fbee9a1dcb4ff5d2b47c6be9f8f4f",
+                "txContents": tx,
+            } }
+        }),
+        json!({
+            "jsonrpc": "2.0", "method": "subscribe",
+            "params": { "subscription": "sub-2", "result": {
+                "hash": "0x6ae2b1a5c1a0e25a4e5b8b4f4d3aab65f0d4a4e3f8d1d52c3b1b6c37f1f0f4a1",
+                "header": { "number": "0x280de80" },
+                "transactions": [tx],
+            } }
+        }),
+    ];
+    let url = crate::mock::serve_ws(Vec::new(), notifications).await;
+
+    let mut client = BloxrouteStream::connect(&url, "key").await.unwrap();
+    let options = SubscribeOptions::new(StreamTopic::NewTxs)
+        .with_filters("{to} == '0x5c952063c7fc8610ffdb798152d69f0b9550762b'");
+    assert_eq!(
+        client
+            .subscribe(StreamTopic::NewTxs, options)
+            .await
+            .unwrap(),
+        "sub-1"
+    );
+    let options = SubscribeOptions::new(StreamTopic::BdnBlocks);
+    assert_eq!(
+        client
+            .subscribe(StreamTopic::BdnBlocks, options)
+            .await
+            .unwrap(),
+        "sub-2"
+    );
+
+    let mut stream = Box::pin(client.into_stream());
+    let Notification { event, .. } = stream.next().await.unwrap().unwrap();
+    let StreamEvent::Transaction { tx: Some(tx), .. } = event else {
+        panic!("expected a transaction with contents");
+    };
+    assert_eq!(
+        alloy::consensus::Transaction::value(&*tx),
+        alloy::primitives::U256::from(1_000_000_000_000_000_000u128)
+    );
+
+    let Notification { event, .. } = stream.next().await.unwrap().unwrap();
+    let StreamEvent::Block(block) = event else {
+        panic!("expected a block");
+    };
+    assert_eq!(block.number(), Some(42_000_000));
+    assert_eq!(block.transactions.len(), 1);
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_subscribe_skips_malformed_frames() {
+    let notifications = vec![json!({
+        "jsonrpc": "2.0", "method": "subscribe",
+        "params": { "subscription": "sub-1", "result": {
+            "txHash": "0x1f0fd85f4fcc7eba0db0c1dbb1cc3c6a4c3fbee9a1dcb4ff5d2b47c6be9f8f4f",
+        } }
+    })];
+    let before_ack = vec![
+        String::from("not json"),
+        json!({ "jsonrpc": "2.0", "method": "subscribe", "params": { "result": {} } }).to_string(),
+    ];
+    let url = crate::mock::serve_ws(before_ack, notifications).await;
+
+    let mut client = BloxrouteStream::connect(&url, "key").await.unwrap();
+    let options = SubscribeOptions::new(StreamTopic::NewTxs);
+    assert_eq!(
+        client
+            .subscribe(StreamTopic::NewTxs, options)
+            .await
+            .unwrap(),
+        "sub-1"
+    );
+
+    // The malformed frames are reported in order, then the stream goes on
+    assert!(matches!(
+        client.next_notification().await,
+        Some(Err(BloxrouteError::InvalidResponse(_)))
+    ));
+    assert!(matches!(
+        client.next_notification().await,
+        Some(Err(BloxrouteError::InvalidResponse(_)))
+    ));
+    let Notification { event, .. } = client.next_notification().await.unwrap().unwrap();
+    assert!(matches!(event, StreamEvent::Transaction { tx: None, .. }));
+    assert!(client.next_notification().await.is_none());
+}
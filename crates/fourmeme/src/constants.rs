@@ -1,7 +1,9 @@
-use alloy::primitives::{Address, B256, address, b256};
+use alloy::primitives::{Address, B256, U256, address, b256, uint};
 
 /// Fourmeme
 pub const FOURMEME_CONTRACT: Address = address!("0x5c952063c7fc8610FFDB798152D69F0B9550762b");
+/// Fourmeme TokenManagerHelper3, read-only quotes and token info
+pub const FOURMEME_HELPER: Address = address!("0xF251F83e40a78868FcfA3FA4599Dad6494E46034");
 /// LiquidityAdded event topic
 pub const LIQUIDITY_ADDED_TOPIC: B256 =
     b256!("0xc18aa71171b358b706fe3dd345299685ba21a5316c66ffa9e319268b033c44b0");
@@ -11,3 +13,16 @@ pub const TOKEN_SALE_TOPIC: B256 =
     b256!("0x0a5575b3648bae2210cee56bf33254cc1ddfbc7bf637c0af2ac18b14fb1bae19");
 pub const TOKEN_CREATE_TOPIC: B256 =
     b256!("0x396d5e902b675b032348d3d2e9517ee8f0c4a926603fbc075d3d282ff00cad20");
+
+/// Virtual BNB reserve of the bonding curve (8 BNB)
+pub const CURVE_VIRTUAL_FUNDS: U256 = uint!(8_000_000_000_000_000_000_U256);
+/// Virtual token reserve on top of the remaining offers (~266.67M tokens)
+pub const CURVE_VIRTUAL_OFFERS: U256 = uint!(266_666_667_000_000_000_000_000_000_U256);
+/// Tokens offered on the bonding curve before migration (800M tokens)
+pub const CURVE_MAX_OFFERS: U256 = uint!(800_000_000_000_000_000_000_000_000_U256);
+/// BNB raised once every offered token is sold (24 BNB)
+pub const CURVE_MAX_FUNDS: U256 = uint!(24_000_000_000_000_000_000_U256);
+/// Trading fee charged on curve trades, in basis points (1%)
+pub const TRADE_FEE_BPS: u16 = 100;
+/// Basis points denominator
+pub const BPS_DENOMINATOR: u16 = 10_000;
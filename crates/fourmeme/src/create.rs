@@ -0,0 +1,368 @@
+use alloy::{
+    primitives::{Address, Bytes, U256, utils::format_ether},
+    rpc::types::TransactionReceipt,
+    signers::Signer,
+    sol_types::SolEvent,
+};
+use anyhow::{Error, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use sender::Sender;
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+use crate::{constants::FOURMEME_CONTRACT, parser::TokenCreate};
+
+/// Fourmeme web API
+pub const FOURMEME_API: &str = "https://four.meme/meme-api";
+/// Category of a launched token when none is set
+pub const DEFAULT_LABEL: &str = "Meme";
+/// Trading fee of the PancakeSwap pair the token migrates to, 0.25%
+pub const DEFAULT_LP_TRADING_FEE_BPS: u16 = 25;
+
+// The Fourmeme API is undocumented: the login fields below are the fixed values the
+// four.meme web app sends when a wallet signs in, and the API expects them as is.
+const LOGIN_REGION: &str = "WEB";
+const LOGIN_LANG_TYPE: &str = "EN";
+const LOGIN_WALLET_NAME: &str = "MetaMask";
+const NETWORK_CODE: &str = "BSC";
+
+/// Metadata of a token to launch
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub description: String,
+    /// Image already uploaded to the Fourmeme CDN
+    pub image_url: String,
+    pub website: Option<String>,
+    pub twitter: Option<String>,
+    pub telegram: Option<String>,
+    /// BNB spent buying the token in the creation transaction, fee included
+    pub pre_buy: U256,
+    /// Category shown on four.meme, `DEFAULT_LABEL` when `None`
+    pub label: Option<String>,
+    /// Trading fee of the PancakeSwap pair, `DEFAULT_LP_TRADING_FEE_BPS` when `None`
+    pub lp_trading_fee_bps: Option<u16>,
+    /// Launch time in milliseconds, the time of the request when `None`
+    pub launch_time: Option<u64>,
+}
+
+impl TokenMetadata {
+    pub fn new(name: impl Into<String>, symbol: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            symbol: symbol.into(),
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_image_url(mut self, image_url: impl Into<String>) -> Self {
+        self.image_url = image_url.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_website(mut self, website: impl Into<String>) -> Self {
+        self.website = Some(website.into());
+        self
+    }
+
+    #[inline]
+    pub fn with_twitter(mut self, twitter: impl Into<String>) -> Self {
+        self.twitter = Some(twitter.into());
+        self
+    }
+
+    #[inline]
+    pub fn with_telegram(mut self, telegram: impl Into<String>) -> Self {
+        self.telegram = Some(telegram.into());
+        self
+    }
+
+    #[inline]
+    pub fn with_pre_buy(mut self, pre_buy: U256) -> Self {
+        self.pre_buy = pre_buy;
+        self
+    }
+
+    #[inline]
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    #[inline]
+    pub fn with_lp_trading_fee_bps(mut self, lp_trading_fee_bps: u16) -> Self {
+        self.lp_trading_fee_bps = Some(lp_trading_fee_bps);
+        self
+    }
+
+    #[inline]
+    pub fn with_launch_time(mut self, launch_time: u64) -> Self {
+        self.launch_time = Some(launch_time);
+        self
+    }
+}
+
+/// Arguments of the `createToken` call, encoded and signed by Fourmeme
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CreatePayload {
+    pub args: Bytes,
+    pub signature: Bytes,
+}
+
+/// Source of signed creation payloads
+///
+/// The token manager only accepts creation arguments signed off-chain by Fourmeme,
+/// implement this trait to mock that step.
+#[async_trait]
+pub trait CreatePayloadSource: Send + Sync {
+    /// Register `metadata` for the `creator` wallet and return the signed creation payload
+    async fn create_payload(
+        &self,
+        creator: &Sender,
+        metadata: &TokenMetadata,
+    ) -> Result<CreatePayload, Error>;
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    code: Value,
+    msg: Option<String>,
+    data: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateResponse {
+    create_arg: Bytes,
+    signature: Bytes,
+}
+
+/// Fourmeme web API client, logging in with the creator wallet
+#[derive(Debug, Clone)]
+pub struct FourMemeApi {
+    client: Client,
+    url: String,
+}
+
+impl Default for FourMemeApi {
+    fn default() -> Self {
+        Self::new(FOURMEME_API)
+    }
+}
+
+impl FourMemeApi {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            url: url.into(),
+        }
+    }
+
+    /// Sign the login nonce with the creator wallet and return the access token
+    async fn login(&self, creator: &Sender) -> Result<String, Error> {
+        let address = creator.address().to_string();
+        let nonce: String = self
+            .post(
+                "/v1/private/user/nonce/generate",
+                None,
+                json!({
+                    "accountAddress": address,
+                    "verifyType": "LOGIN",
+                    "networkCode": NETWORK_CODE,
+                }),
+            )
+            .await?;
+
+        let message = format!("You are sign in Meme {nonce}");
+        let signature = creator.singer.sign_message(message.as_bytes()).await?;
+
+        self.post(
+            "/v1/private/user/login/dex",
+            None,
+            json!({
+                "region": LOGIN_REGION,
+                "langType": LOGIN_LANG_TYPE,
+                "loginIp": "",
+                "inviteCode": "",
+                "verifyInfo": {
+                    "address": address,
+                    "networkCode": NETWORK_CODE,
+                    "signature": signature.to_string(),
+                    "verifyType": "LOGIN",
+                },
+                "walletName": LOGIN_WALLET_NAME,
+            }),
+        )
+        .await
+    }
+
+    async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        access_token: Option<&str>,
+        body: Value,
+    ) -> Result<T, Error> {
+        let mut request = self
+            .client
+            .post(format!("{}{}", self.url, path))
+            .json(&body);
+        if let Some(access_token) = access_token {
+            request = request.header("meme-web-access", access_token);
+        }
+
+        let response: ApiResponse<T> = request.send().await?.error_for_status()?.json().await?;
+        let success = match &response.code {
+            Value::Number(code) => code.as_i64() == Some(0),
+            Value::String(code) => code == "0",
+            _ => false,
+        };
+        match response.data {
+            Some(data) if success => Ok(data),
+            _ => Err(Error::msg(format!(
+                "Fourmeme API {} failed with code {}: {}",
+                path,
+                response.code,
+                response.msg.unwrap_or_default()
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl CreatePayloadSource for FourMemeApi {
+    async fn create_payload(
+        &self,
+        creator: &Sender,
+        metadata: &TokenMetadata,
+    ) -> Result<CreatePayload, Error> {
+        let access_token = self.login(creator).await?;
+        let launch_time = match metadata.launch_time {
+            Some(launch_time) => launch_time,
+            None => std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_millis() as u64,
+        };
+        let lp_trading_fee_bps = metadata
+            .lp_trading_fee_bps
+            .unwrap_or(DEFAULT_LP_TRADING_FEE_BPS);
+        let response: CreateResponse = self
+            .post(
+                "/v1/private/token/create",
+                Some(&access_token),
+                json!({
+                    "name": metadata.name,
+                    "shortName": metadata.symbol,
+                    "desc": metadata.description,
+                    "imgUrl": metadata.image_url,
+                    "webUrl": metadata.website,
+                    "twitterUrl": metadata.twitter,
+                    "telegramUrl": metadata.telegram,
+                    "preSale": format_ether(metadata.pre_buy),
+                    "label": metadata.label.as_deref().unwrap_or(DEFAULT_LABEL),
+                    "lpTradingFee": f64::from(lp_trading_fee_bps) / 10_000.0,
+                    "launchTime": launch_time,
+                }),
+            )
+            .await?;
+
+        Ok(CreatePayload {
+            args: response.create_arg,
+            signature: response.signature,
+        })
+    }
+}
+
+/// Find the `TokenCreate` event emitted by the token manager in `receipt`
+#[inline]
+pub fn token_created(receipt: &TransactionReceipt) -> Option<TokenCreate> {
+    receipt
+        .inner
+        .logs()
+        .iter()
+        .filter(|log| log.address() == FOURMEME_CONTRACT)
+        .find_map(|log| TokenCreate::decode_log(log.as_ref()).ok())
+        .map(|event| event.data)
+}
+
+/// Address of the token created in `receipt`
+#[inline]
+pub fn created_token_address(receipt: &TransactionReceipt) -> Option<Address> {
+    token_created(receipt).map(|event| event.token)
+}
+
+#[test]
+fn test_token_created() {
+    use alloy::{
+        consensus::{Receipt, ReceiptEnvelope, ReceiptWithBloom},
+        primitives::{B256, Bloom, Log as PrimitiveLog, LogData},
+        rpc::types::Log,
+    };
+
+    let receipt = |logs: Vec<Log>| TransactionReceipt {
+        inner: ReceiptEnvelope::Legacy(ReceiptWithBloom::new(
+            Receipt {
+                status: true.into(),
+                cumulative_gas_used: 0,
+                logs,
+            },
+            Bloom::default(),
+        )),
+        transaction_hash: B256::ZERO,
+        transaction_index: None,
+        block_hash: None,
+        block_number: None,
+        gas_used: 0,
+        effective_gas_price: 0,
+        blob_gas_used: None,
+        blob_gas_price: None,
+        from: Address::ZERO,
+        to: Some(FOURMEME_CONTRACT),
+        contract_address: None,
+    };
+    let log = |address: Address, data| Log {
+        inner: PrimitiveLog { address, data },
+        ..Default::default()
+    };
+
+    let event = TokenCreate {
+        creator: Address::repeat_byte(0x11),
+        token: Address::repeat_byte(0x22),
+        requestId: U256::from(7),
+        name: String::from("Test"),
+        symbol: String::from("TEST"),
+        totalSupply: U256::from(1_000_000_000u64),
+        launchTime: U256::from(1_700_000_000u64),
+        launchFee: U256::ZERO,
+    };
+    let transfer = log(Address::repeat_byte(0x22), LogData::empty());
+    let created = log(FOURMEME_CONTRACT, event.encode_log_data());
+
+    let with_event = receipt(vec![transfer.clone(), created.clone()]);
+    let decoded = token_created(&with_event).unwrap();
+    assert_eq!(
+        (decoded.creator, decoded.token),
+        (event.creator, event.token)
+    );
+    assert_eq!(
+        (&decoded.name, &decoded.symbol),
+        (&event.name, &event.symbol)
+    );
+    assert_eq!(decoded.totalSupply, event.totalSupply);
+    assert_eq!(created_token_address(&with_event), Some(event.token));
+
+    // Emitted by another contract, or missing
+    let spoofed = log(Address::repeat_byte(0x33), event.encode_log_data());
+    assert_eq!(created_token_address(&receipt(vec![spoofed])), None);
+    assert_eq!(created_token_address(&receipt(vec![transfer])), None);
+    assert!(token_created(&receipt(Vec::new())).is_none());
+}
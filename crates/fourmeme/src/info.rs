@@ -0,0 +1,105 @@
+use abi::{FourMemeContract::_tokenInfosReturn, FourMemeHelper::getTokenInfoReturn};
+use alloy::primitives::{Address, U256};
+
+use crate::quote;
+
+/// On-chain state of a Fourmeme token
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub token: Address,
+    /// Creator of the token, `None` when the token manager does not expose it
+    pub creator: Option<Address>,
+    /// Quote token of the curve, zero for BNB
+    pub quote: Address,
+    /// Launch time in seconds
+    pub launch_time: u64,
+    pub total_supply: U256,
+    /// Tokens still offered on the curve
+    pub offers: U256,
+    /// Tokens offered on the curve at launch
+    pub max_offers: U256,
+    /// Funds raised by the curve so far
+    pub funds: U256,
+    /// Funds raised once the curve is complete
+    pub max_funds: U256,
+    /// Price of the last trade, in quote wei per token
+    pub last_price: U256,
+    /// Trading fee rate in basis points
+    pub trading_fee_rate: U256,
+    /// Whether the liquidity has been moved to PancakeSwap
+    pub migrated: bool,
+}
+
+impl TokenInfo {
+    /// Merge the helper and token manager views of `token`
+    #[inline]
+    pub fn from_parts(
+        token: Address,
+        helper: &getTokenInfoReturn,
+        manager: &_tokenInfosReturn,
+        creator: Option<Address>,
+    ) -> Self {
+        Self {
+            token,
+            creator: creator.filter(|creator| !creator.is_zero()),
+            quote: helper.quote,
+            launch_time: helper.launchTime.saturating_to(),
+            total_supply: manager.totalSupply,
+            offers: helper.offers,
+            max_offers: helper.maxOffers,
+            funds: helper.funds,
+            max_funds: helper.maxFunds,
+            last_price: helper.lastPrice,
+            trading_fee_rate: helper.tradingFeeRate,
+            migrated: helper.liquidityAdded,
+        }
+    }
+
+    /// Share of the curve already sold, in basis points
+    #[inline]
+    pub fn progress_bps(&self) -> u16 {
+        quote::progress_bps(self.max_offers, self.offers)
+    }
+}
+
+#[test]
+fn test_from_parts() {
+    let token = Address::repeat_byte(0x11);
+    let one_token = U256::from(10u64).pow(U256::from(18));
+    let helper = getTokenInfoReturn {
+        version: U256::from(2),
+        tokenManager: Address::repeat_byte(0x22),
+        quote: Address::ZERO,
+        lastPrice: U256::from(5_000_000_000u64),
+        tradingFeeRate: U256::from(100),
+        minTradingFee: U256::ZERO,
+        launchTime: U256::from(1_700_000_000u64),
+        offers: U256::from(600_000_000u64) * one_token,
+        maxOffers: U256::from(800_000_000u64) * one_token,
+        funds: U256::from(3u64) * one_token,
+        maxFunds: U256::from(24u64) * one_token,
+        liquidityAdded: false,
+    };
+    let manager = _tokenInfosReturn {
+        base: token,
+        quote: Address::ZERO,
+        template: U256::ZERO,
+        totalSupply: U256::from(1_000_000_000u64) * one_token,
+        maxOffers: helper.maxOffers,
+        maxRaising: helper.maxFunds,
+        launchTime: helper.launchTime,
+        offers: helper.offers,
+        funds: helper.funds,
+        lastPrice: helper.lastPrice,
+        K: U256::ZERO,
+        T: U256::ZERO,
+        status: U256::ZERO,
+    };
+
+    let info = TokenInfo::from_parts(token, &helper, &manager, Some(Address::ZERO));
+    assert_eq!(info.creator, None);
+    assert_eq!(info.launch_time, 1_700_000_000);
+    assert_eq!(info.total_supply, manager.totalSupply);
+    assert_eq!(info.progress_bps(), 2_500);
+    assert!(!info.migrated);
+}
@@ -1,66 +1,347 @@
 pub mod constants;
+pub mod create;
+pub mod info;
 pub mod parser;
+pub mod quote;
 
 use abi::{
-    FourMemeContract::*,
+    FourMemeContract::{self, *},
+    FourMemeHelper::{self, getTokenInfoReturn, tryBuyReturn, trySellReturn},
     IERC20::{IERC20Calls, approveCall},
 };
 use alloy::{
-    consensus::{SignableTransaction, TxEnvelope, TypedTransaction},
-    eips::Encodable2718,
-    hex,
-    network::{TransactionBuilder, TxSigner},
+    network::TransactionBuilder,
     primitives::{Address, U256},
-    providers::{DynProvider, Provider},
+    providers::{DynProvider, MulticallItem, Provider},
     rpc::types::{TransactionInput, TransactionReceipt, TransactionRequest},
-    signers::local::PrivateKeySigner,
     sol_types::SolInterface,
 };
 use anyhow::{Error, Result};
-use rpc::Rpc;
+use rpc::{Rpc, wallet::Wallet};
+use sender::{Sender, SignedTransaction};
 use std::sync::Arc;
 
-use crate::constants::FOURMEME_CONTRACT;
+use crate::{
+    constants::{FOURMEME_CONTRACT, FOURMEME_HELPER},
+    create::{CreatePayload, CreatePayloadSource, TokenMetadata, created_token_address},
+    info::TokenInfo,
+    quote::{TokenCurve, add_slippage, apply_slippage},
+};
 
+#[derive(Clone)]
 pub struct FourMeme {
     client: Arc<DynProvider>,
-    signer: PrivateKeySigner,
+    sender: Sender,
 }
 
 impl FourMeme {
-    pub async fn init(rpc: Rpc, signer: PrivateKeySigner) -> Result<Self, Error> {
+    pub async fn init(rpc: Rpc, sender: Sender) -> Result<Self, Error> {
+        if sender.chain_id() != rpc.chain_id {
+            return Err(Error::msg(format!(
+                "Sender chain id {} does not match rpc chain id {}",
+                sender.chain_id(),
+                rpc.chain_id
+            )));
+        }
         let client = Arc::new(rpc.client);
 
-        Ok(Self { client, signer })
+        Ok(Self { client, sender })
+    }
+
+    /// Trade from another wallet of the pool, sharing the same provider
+    ///
+    /// # Arguments
+    ///
+    /// * `wallet` - The wallet to sign and send from, e.g. `rpc.wallets.next()`
+    ///
+    #[inline]
+    pub fn for_wallet(&self, wallet: &Wallet) -> Self {
+        Self {
+            client: Arc::clone(&self.client),
+            sender: wallet.sender.clone(),
+        }
+    }
+
+    /// Minimum amount of tokens to accept when buying with `ether_spent`
+    ///
+    /// # Arguments
+    ///
+    /// * `curve` - The curve of the token, e.g. from `token_curve`, at its latest state
+    /// * `ether_spent` - The amount of ether to spend
+    /// * `slippage_bps` - The slippage tolerance in basis points
+    ///
+    #[inline]
+    pub fn min_amount_out(&self, curve: &TokenCurve, ether_spent: U256, slippage_bps: u16) -> U256 {
+        apply_slippage(
+            curve.curve.quote_buy(&curve.state, ether_spent),
+            slippage_bps,
+        )
+    }
+
+    /// Maximum funds to spend when buying exactly `amount` tokens
+    ///
+    /// # Arguments
+    ///
+    /// * `curve` - The curve of the token, e.g. from `token_curve`, at its latest state
+    /// * `amount` - The exact amount of token to buy
+    /// * `slippage_bps` - The slippage tolerance in basis points
+    ///
+    #[inline]
+    pub fn max_funds_in(
+        &self,
+        curve: &TokenCurve,
+        amount: U256,
+        slippage_bps: u16,
+    ) -> Result<U256, Error> {
+        let funds = curve
+            .curve
+            .quote_buy_exact(&curve.state, amount)
+            .ok_or_else(|| {
+                Error::msg(format!(
+                    "Curve only offers {} tokens, cannot buy {}",
+                    curve.state.offers, amount
+                ))
+            })?;
+        Ok(add_slippage(funds, slippage_bps))
+    }
+
+    /// Minimum funds to accept when selling `amount` tokens
+    ///
+    /// # Arguments
+    ///
+    /// * `curve` - The curve of the token, e.g. from `token_curve`, at its latest state
+    /// * `amount` - The amount of token to sell
+    /// * `slippage_bps` - The slippage tolerance in basis points
+    ///
+    #[inline]
+    pub fn min_funds_out(&self, curve: &TokenCurve, amount: U256, slippage_bps: u16) -> U256 {
+        apply_slippage(curve.curve.quote_sell(&curve.state, amount), slippage_bps)
+    }
+
+    /// Read the bonding curve of a token and its current state from the token manager
+    ///
+    /// Every token has a curve of its own, set by its launch template and quote asset.
+    /// Keep the curve and update its state from trade events with `TokenCurve::with_state`.
+    #[inline]
+    pub async fn token_curve(&self, token: Address) -> Result<TokenCurve, Error> {
+        let info = self.token_manager_info(token).await?;
+        TokenCurve::from_token_manager(&info)
+            .ok_or_else(|| Error::msg(format!("No bonding curve recorded for {}", token)))
+    }
+
+    /// Quote a buy with the helper contract
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token to buy
+    /// * `amount` - The exact amount of token to buy, or zero to quote by `funds`
+    /// * `funds` - The amount of ether to spend, or zero to quote by `amount`
+    ///
+    #[inline]
+    pub async fn try_buy(
+        &self,
+        token: Address,
+        amount: U256,
+        funds: U256,
+    ) -> Result<tryBuyReturn, Error> {
+        let quote = FourMemeHelper::new(FOURMEME_HELPER, &self.client)
+            .tryBuy(token, amount, funds)
+            .call()
+            .await?;
+        Ok(quote)
+    }
+
+    /// Quote a sell with the helper contract
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token to sell
+    /// * `amount` - The amount of token to sell
+    ///
+    #[inline]
+    pub async fn try_sell(&self, token: Address, amount: U256) -> Result<trySellReturn, Error> {
+        let quote = FourMemeHelper::new(FOURMEME_HELPER, &self.client)
+            .trySell(token, amount)
+            .call()
+            .await?;
+        Ok(quote)
+    }
+
+    /// Read the token info from the helper contract
+    #[inline]
+    pub async fn get_token_info(&self, token: Address) -> Result<getTokenInfoReturn, Error> {
+        let info = FourMemeHelper::new(FOURMEME_HELPER, &self.client)
+            .getTokenInfo(token)
+            .call()
+            .await?;
+        Ok(info)
+    }
+
+    /// Read the raw token info stored by the token manager, including the curve constants
+    #[inline]
+    pub async fn token_manager_info(&self, token: Address) -> Result<_tokenInfosReturn, Error> {
+        let info = FourMemeContract::new(FOURMEME_CONTRACT, &self.client)
+            ._tokenInfos(token)
+            .call()
+            .await?;
+        Ok(info)
+    }
+
+    /// Read the launch template with the given index
+    #[inline]
+    pub async fn template(&self, index: U256) -> Result<_templatesReturn, Error> {
+        let template = FourMemeContract::new(FOURMEME_CONTRACT, &self.client)
+            ._templates(index)
+            .call()
+            .await?;
+        Ok(template)
+    }
+
+    /// Trading fee rate in basis points
+    #[inline]
+    pub async fn trading_fee_rate(&self) -> Result<U256, Error> {
+        let fee_rate = FourMemeContract::new(FOURMEME_CONTRACT, &self.client)
+            ._tradingFeeRate()
+            .call()
+            .await?;
+        Ok(fee_rate)
+    }
+
+    /// Minimum trading fee in wei
+    #[inline]
+    pub async fn min_trading_fee(&self) -> Result<U256, Error> {
+        let fee = FourMemeContract::new(FOURMEME_CONTRACT, &self.client)
+            ._minTradingFee()
+            .call()
+            .await?;
+        Ok(fee)
+    }
+
+    /// Fee charged to launch a token, in wei
+    #[inline]
+    pub async fn launch_fee(&self) -> Result<U256, Error> {
+        let fee = FourMemeContract::new(FOURMEME_CONTRACT, &self.client)
+            ._launchFee()
+            .call()
+            .await?;
+        Ok(fee)
+    }
+
+    /// Address receiving the trading and launch fees
+    #[inline]
+    pub async fn fee_recipient(&self) -> Result<Address, Error> {
+        let recipient = FourMemeContract::new(FOURMEME_CONTRACT, &self.client)
+            ._feeRecipient()
+            .call()
+            .await?;
+        Ok(recipient)
+    }
+
+    /// Read the on-chain state of a token
+    #[inline]
+    pub async fn token_info(&self, token: Address) -> Result<TokenInfo, Error> {
+        let mut infos = self.token_infos(&[token]).await?;
+        infos
+            .pop()
+            .ok_or_else(|| Error::msg(format!("No token info returned for {}", token)))?
+    }
+
+    /// Read the on-chain state of many tokens at once
+    ///
+    /// The helper, token manager and creator reads are each batched into a single multicall.
+    /// Every call may fail on its own, so a reverting or unknown token only fails its own
+    /// entry; the outer error is reserved for the multicalls themselves.
+    ///
+    /// # Arguments
+    ///
+    /// * `tokens` - The addresses of the tokens, the result keeps the same order
+    ///
+    #[inline]
+    pub async fn token_infos(
+        &self,
+        tokens: &[Address],
+    ) -> Result<Vec<Result<TokenInfo, Error>>, Error> {
+        if tokens.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let provider = &*self.client;
+        let helper = FourMemeHelper::new(FOURMEME_HELPER, provider);
+        let manager = FourMemeContract::new(FOURMEME_CONTRACT, provider);
+
+        let helper_calls = provider.multicall().dynamic().extend_calls(
+            tokens
+                .iter()
+                .map(|token| helper.getTokenInfo(*token).into_call(true)),
+        );
+        let manager_calls = provider.multicall().dynamic().extend_calls(
+            tokens
+                .iter()
+                .map(|token| manager._tokenInfos(*token).into_call(true)),
+        );
+        let creator_calls = provider.multicall().dynamic().extend_calls(
+            tokens
+                .iter()
+                .map(|token| manager._tokenCreators(*token).into_call(true)),
+        );
+
+        let (helper_infos, manager_infos, creators) = tokio::try_join!(
+            helper_calls.aggregate3(),
+            manager_calls.aggregate3(),
+            creator_calls.aggregate3(),
+        )?;
+
+        Ok(tokens
+            .iter()
+            .zip(helper_infos)
+            .zip(manager_infos)
+            .zip(creators)
+            .map(|(((token, helper_info), manager_info), creator)| {
+                let helper_info = helper_info
+                    .map_err(|_| Error::msg(format!("getTokenInfo reverted for {}", token)))?;
+                let manager_info = manager_info
+                    .map_err(|_| Error::msg(format!("_tokenInfos reverted for {}", token)))?;
+                Ok(TokenInfo::from_parts(
+                    *token,
+                    &helper_info,
+                    &manager_info,
+                    creator.ok(),
+                ))
+            })
+            .collect())
     }
+
     /// Handle the buy transaction
     ///
     /// # Arguments
     ///
     /// * `ether_spent` - The amount of ether to spend
     /// * `token` - The address of the token to buy
-    /// * `token_bought` - The amount of token to buy
+    /// * `curve` - The curve of the token at its latest state, used to quote the output
+    /// * `slippage_bps` - The slippage tolerance in basis points
     ///
     #[inline]
     pub async fn buy_token(
         &self,
         ether_spent: U256,
         token: Address,
+        curve: &TokenCurve,
+        slippage_bps: u16,
         gas_price: u128,
     ) -> Result<TransactionReceipt, Error> {
+        let min_amount = self.min_amount_out(curve, ether_spent, slippage_bps);
         let buy_tx = TransactionRequest::default()
+            .with_from(self.sender.address())
             .with_to(FOURMEME_CONTRACT)
             .with_value(ether_spent)
             .with_gas_limit(200000_u64)
             .with_gas_price(gas_price)
             .with_input(
-                FourMemeContractCalls::buyTokenAMAP {
-                    0: buyTokenAMAPCall {
-                        tokenAddress: token,
-                        funds: ether_spent,
-                        minAmount: U256::ZERO,
-                    },
-                }
+                FourMemeContractCalls::buyTokenAMAP_0(buyTokenAMAP_0Call {
+                    tokenAddress: token,
+                    funds: ether_spent,
+                    minAmount: min_amount,
+                })
                 .abi_encode(),
             );
 
@@ -76,64 +357,303 @@ impl FourMeme {
         &self,
         ether_spent: U256,
         token: Address,
+        curve: &TokenCurve,
+        slippage_bps: u16,
         gas_price: u128,
         nonce: u64,
-    ) -> Result<String, Error> {
-        // Get sender address and chain_id for EIP-155 replay protection
-        let sender_address = self.signer.address();
-        let chain_id = self.client.get_chain_id().await?;
-
-        let signer = self.signer.clone();
+    ) -> Result<SignedTransaction, Error> {
+        let min_amount = self.min_amount_out(curve, ether_spent, slippage_bps);
 
         let buy_tx = TransactionRequest::default()
-            .from(sender_address)
             .to(FOURMEME_CONTRACT)
             .value(ether_spent)
             .gas_limit(200000_u64)
             .gas_price(gas_price)
             .nonce(nonce)
             .input(TransactionInput::new(
-                FourMemeContractCalls::buyTokenAMAP {
-                    0: buyTokenAMAPCall {
-                        tokenAddress: token,
-                        funds: ether_spent,
-                        minAmount: U256::ZERO,
-                    },
-                }
+                FourMemeContractCalls::buyTokenAMAP_0(buyTokenAMAP_0Call {
+                    tokenAddress: token,
+                    funds: ether_spent,
+                    minAmount: min_amount,
+                })
                 .abi_encode()
                 .into(),
             ));
 
-        let typed_tx = buy_tx
-            .build_typed_tx()
-            .map_err(|e| Error::msg(format!("Failed to build typed transaction: {:?}", e)))?;
-
-        let signed_envelope: TxEnvelope = match typed_tx {
-            TypedTransaction::Legacy(mut tx) => {
-                tx.chain_id = Some(chain_id);
-                let sig = signer
-                    .sign_transaction(&mut tx)
-                    .await
-                    .map_err(|e| Error::msg(format!("Failed to sign transaction: {:?}", e)))?;
-                tx.into_signed(sig).into()
-            }
-            TypedTransaction::Eip1559(mut tx) => {
-                tx.chain_id = chain_id;
-                let sig = signer
-                    .sign_transaction(&mut tx)
-                    .await
-                    .map_err(|e| Error::msg(format!("Failed to sign transaction: {:?}", e)))?;
-                tx.into_signed(sig).into()
-            }
-            _ => {
-                return Err(Error::msg("Unsupported transaction type"));
-            }
-        };
-
-        let raw_tx_bytes: Vec<u8> = signed_envelope.encoded_2718();
-        let raw_tx_hex = hex::encode(&raw_tx_bytes);
-
-        Ok(raw_tx_hex)
+        self.sender.sign(buy_tx).await
+    }
+
+    /// Buy an exact amount of the token, spending at most `max_funds`
+    ///
+    /// The whole `max_funds` is sent as value and the unspent part is refunded by the contract.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token to buy
+    /// * `amount` - The exact amount of token to buy
+    /// * `max_funds` - The maximum amount of ether to spend, e.g. from `max_funds_in`
+    ///
+    #[inline]
+    pub async fn buy_token_exact(
+        &self,
+        token: Address,
+        amount: U256,
+        max_funds: U256,
+        gas_price: u128,
+    ) -> Result<TransactionReceipt, Error> {
+        let buy_tx = self
+            .buy_token_exact_request(token, amount, max_funds, gas_price)
+            .with_from(self.sender.address());
+
+        let pending_tx = self.client.send_transaction(buy_tx).await?;
+        let receipt = pending_tx.get_receipt().await?;
+        Ok(receipt)
+    }
+
+    /// Buy an exact amount of the token with a signed transaction
+    /// This function returns a signed transaction without submitting it
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token to buy
+    /// * `amount` - The exact amount of token to buy
+    /// * `max_funds` - The maximum amount of ether to spend
+    /// * `gas_price` - Gas price in wei
+    /// * `nonce` - Transaction nonce
+    ///
+    #[inline]
+    pub async fn buy_token_exact_signed(
+        &self,
+        token: Address,
+        amount: U256,
+        max_funds: U256,
+        gas_price: u128,
+        nonce: u64,
+    ) -> Result<SignedTransaction, Error> {
+        let buy_tx = self
+            .buy_token_exact_request(token, amount, max_funds, gas_price)
+            .with_nonce(nonce);
+
+        self.sender.sign(buy_tx).await
+    }
+
+    #[inline]
+    fn buy_token_exact_request(
+        &self,
+        token: Address,
+        amount: U256,
+        max_funds: U256,
+        gas_price: u128,
+    ) -> TransactionRequest {
+        TransactionRequest::default()
+            .with_to(FOURMEME_CONTRACT)
+            .with_value(max_funds)
+            .with_gas_limit(200000_u64)
+            .with_gas_price(gas_price)
+            .with_input(
+                FourMemeContractCalls::buyToken_0(buyToken_0Call {
+                    token,
+                    amount,
+                    maxFunds: max_funds,
+                })
+                .abi_encode(),
+            )
+    }
+
+    /// Buy the token for another address
+    ///
+    /// # Arguments
+    ///
+    /// * `recipient` - The address receiving the tokens
+    /// * `ether_spent` - The amount of ether to spend
+    /// * `token` - The address of the token to buy
+    /// * `min_amount` - The minimum amount of token to receive, e.g. from `try_buy`
+    ///
+    #[inline]
+    pub async fn buy_token_to(
+        &self,
+        recipient: Address,
+        ether_spent: U256,
+        token: Address,
+        min_amount: U256,
+        gas_price: u128,
+    ) -> Result<TransactionReceipt, Error> {
+        let buy_tx =
+            Self::buy_token_to_request(recipient, ether_spent, token, min_amount, gas_price)
+                .with_from(self.sender.address());
+
+        let pending_tx = self.client.send_transaction(buy_tx).await?;
+        let receipt = pending_tx.get_receipt().await?;
+        Ok(receipt)
+    }
+
+    /// Buy the token for another address with a signed transaction
+    #[inline]
+    pub async fn buy_token_to_signed(
+        &self,
+        recipient: Address,
+        ether_spent: U256,
+        token: Address,
+        min_amount: U256,
+        gas_price: u128,
+        nonce: u64,
+    ) -> Result<SignedTransaction, Error> {
+        let buy_tx =
+            Self::buy_token_to_request(recipient, ether_spent, token, min_amount, gas_price)
+                .with_nonce(nonce);
+
+        self.sender.sign(buy_tx).await
+    }
+
+    #[inline]
+    fn buy_token_to_request(
+        recipient: Address,
+        ether_spent: U256,
+        token: Address,
+        min_amount: U256,
+        gas_price: u128,
+    ) -> TransactionRequest {
+        TransactionRequest::default()
+            .with_to(FOURMEME_CONTRACT)
+            .with_value(ether_spent)
+            .with_gas_limit(200000_u64)
+            .with_gas_price(gas_price)
+            .with_input(
+                FourMemeContractCalls::buyTokenAMAP_2(buyTokenAMAP_2Call {
+                    token,
+                    to: recipient,
+                    funds: ether_spent,
+                    minAmount: min_amount,
+                })
+                .abi_encode(),
+            )
+    }
+
+    /// Buy an exact amount of the token for another address, spending at most `max_funds`
+    ///
+    /// # Arguments
+    ///
+    /// * `recipient` - The address receiving the tokens
+    /// * `token` - The address of the token to buy
+    /// * `amount` - The exact amount of token to buy
+    /// * `max_funds` - The maximum amount of ether to spend, e.g. from `max_funds_in`
+    ///
+    #[inline]
+    pub async fn buy_token_exact_to(
+        &self,
+        recipient: Address,
+        token: Address,
+        amount: U256,
+        max_funds: U256,
+        gas_price: u128,
+    ) -> Result<TransactionReceipt, Error> {
+        let buy_tx =
+            Self::buy_token_exact_to_request(recipient, token, amount, max_funds, gas_price)
+                .with_from(self.sender.address());
+
+        let pending_tx = self.client.send_transaction(buy_tx).await?;
+        let receipt = pending_tx.get_receipt().await?;
+        Ok(receipt)
+    }
+
+    /// Buy an exact amount of the token for another address with a signed transaction
+    #[inline]
+    pub async fn buy_token_exact_to_signed(
+        &self,
+        recipient: Address,
+        token: Address,
+        amount: U256,
+        max_funds: U256,
+        gas_price: u128,
+        nonce: u64,
+    ) -> Result<SignedTransaction, Error> {
+        let buy_tx =
+            Self::buy_token_exact_to_request(recipient, token, amount, max_funds, gas_price)
+                .with_nonce(nonce);
+
+        self.sender.sign(buy_tx).await
+    }
+
+    #[inline]
+    fn buy_token_exact_to_request(
+        recipient: Address,
+        token: Address,
+        amount: U256,
+        max_funds: U256,
+        gas_price: u128,
+    ) -> TransactionRequest {
+        TransactionRequest::default()
+            .with_to(FOURMEME_CONTRACT)
+            .with_value(max_funds)
+            .with_gas_limit(200000_u64)
+            .with_gas_price(gas_price)
+            .with_input(
+                FourMemeContractCalls::buyToken_2(buyToken_2Call {
+                    token,
+                    to: recipient,
+                    amount,
+                    maxFunds: max_funds,
+                })
+                .abi_encode(),
+            )
+    }
+
+    /// Launch a new token and return its address
+    ///
+    /// The launch fee and the optional pre-buy are sent as value.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source of the signed creation payload, e.g. `FourMemeApi`
+    /// * `metadata` - The metadata of the token to launch
+    ///
+    #[inline]
+    pub async fn create_token(
+        &self,
+        source: &dyn CreatePayloadSource,
+        metadata: &TokenMetadata,
+        gas_price: u128,
+    ) -> Result<Address, Error> {
+        let payload = source.create_payload(&self.sender, metadata).await?;
+        let value = self.launch_fee().await? + metadata.pre_buy;
+        let create_tx =
+            Self::create_token_request(payload, value, gas_price).with_from(self.sender.address());
+
+        let pending_tx = self.client.send_transaction(create_tx).await?;
+        let receipt = pending_tx.get_receipt().await?;
+        if !receipt.status() {
+            return Err(Error::msg(format!(
+                "Token creation {} reverted",
+                receipt.transaction_hash
+            )));
+        }
+
+        created_token_address(&receipt).ok_or_else(|| {
+            Error::msg(format!(
+                "No TokenCreate event in receipt {}",
+                receipt.transaction_hash
+            ))
+        })
+    }
+
+    #[inline]
+    fn create_token_request(
+        payload: CreatePayload,
+        value: U256,
+        gas_price: u128,
+    ) -> TransactionRequest {
+        TransactionRequest::default()
+            .with_to(FOURMEME_CONTRACT)
+            .with_value(value)
+            .with_gas_limit(2_000_000_u64)
+            .with_gas_price(gas_price)
+            .with_input(
+                FourMemeContractCalls::createToken(createTokenCall {
+                    args: payload.args,
+                    signature: payload.signature,
+                })
+                .abi_encode(),
+            )
     }
 
     /// Approve unlimited allowance for the fourmeme contract
@@ -149,14 +669,13 @@ impl FourMeme {
         gas_price: u128,
     ) -> Result<TransactionReceipt, Error> {
         let approve_tx = TransactionRequest::default()
+            .with_from(self.sender.address())
             .with_to(token)
             .with_input(
-                IERC20Calls::approve {
-                    0: approveCall {
-                        spender: FOURMEME_CONTRACT,
-                        allowance: U256::MAX,
-                    },
-                }
+                IERC20Calls::approve(approveCall {
+                    spender: FOURMEME_CONTRACT,
+                    allowance: U256::MAX,
+                })
                 .abi_encode(),
             )
             .with_gas_price(gas_price);
@@ -166,6 +685,39 @@ impl FourMeme {
         Ok(receipt)
     }
 
+    /// Approve unlimited allowance for the fourmeme contract with a signed transaction
+    /// This function returns a signed transaction without submitting it
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token to approve
+    /// * `gas_price` - Gas price in wei
+    /// * `nonce` - Transaction nonce
+    ///
+    #[inline]
+    pub async fn approve_token_signed(
+        &self,
+        token: Address,
+        gas_price: u128,
+        nonce: u64,
+    ) -> Result<SignedTransaction, Error> {
+        let approve_tx = TransactionRequest::default()
+            .to(token)
+            .gas_limit(100000_u64)
+            .gas_price(gas_price)
+            .nonce(nonce)
+            .input(TransactionInput::new(
+                IERC20Calls::approve(approveCall {
+                    spender: FOURMEME_CONTRACT,
+                    allowance: U256::MAX,
+                })
+                .abi_encode()
+                .into(),
+            ));
+
+        self.sender.sign(approve_tx).await
+    }
+
     /// Sell the token for ether
     ///
     /// # Arguments
@@ -181,17 +733,16 @@ impl FourMeme {
         gas_price: u128,
     ) -> Result<TransactionReceipt, Error> {
         let sell_tx = TransactionRequest::default()
+            .with_from(self.sender.address())
             .with_to(FOURMEME_CONTRACT)
             .with_value(U256::ZERO)
             .with_gas_limit(200000_u64)
             .with_gas_price(gas_price)
             .with_input(
-                FourMemeContractCalls::sellToken {
-                    0: sellTokenCall {
-                        userAddress: token,
-                        tokenQty: amount,
-                    },
-                }
+                FourMemeContractCalls::sellToken_0(sellToken_0Call {
+                    userAddress: token,
+                    tokenQty: amount,
+                })
                 .abi_encode(),
             );
 
@@ -199,4 +750,274 @@ impl FourMeme {
         let receipt = pending_tx.get_receipt().await?;
         Ok(receipt)
     }
+
+    /// Sell the token for ether with a signed transaction
+    /// This function returns a signed transaction without submitting it
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token to sell
+    /// * `amount` - The amount of token to sell
+    /// * `gas_price` - Gas price in wei
+    /// * `nonce` - Transaction nonce
+    ///
+    #[inline]
+    pub async fn sell_token_signed(
+        &self,
+        token: Address,
+        amount: U256,
+        gas_price: u128,
+        nonce: u64,
+    ) -> Result<SignedTransaction, Error> {
+        let sell_tx = TransactionRequest::default()
+            .to(FOURMEME_CONTRACT)
+            .value(U256::ZERO)
+            .gas_limit(200000_u64)
+            .gas_price(gas_price)
+            .nonce(nonce)
+            .input(TransactionInput::new(
+                FourMemeContractCalls::sellToken_0(sellToken_0Call {
+                    userAddress: token,
+                    tokenQty: amount,
+                })
+                .abi_encode()
+                .into(),
+            ));
+
+        self.sender.sign(sell_tx).await
+    }
+
+    /// Sell the token for at least `min_funds` ether
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token to sell
+    /// * `amount` - The amount of token to sell
+    /// * `min_funds` - The minimum amount of ether to receive, e.g. from `min_funds_out`
+    ///
+    #[inline]
+    pub async fn sell_token_min_funds(
+        &self,
+        token: Address,
+        amount: U256,
+        min_funds: U256,
+        gas_price: u128,
+    ) -> Result<TransactionReceipt, Error> {
+        let sell_tx = self
+            .sell_token_min_funds_request(token, amount, min_funds, gas_price)
+            .with_from(self.sender.address());
+
+        let pending_tx = self.client.send_transaction(sell_tx).await?;
+        let receipt = pending_tx.get_receipt().await?;
+        Ok(receipt)
+    }
+
+    /// Sell the token for at least `min_funds` ether with a signed transaction
+    /// This function returns a signed transaction without submitting it
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token to sell
+    /// * `amount` - The amount of token to sell
+    /// * `min_funds` - The minimum amount of ether to receive
+    /// * `gas_price` - Gas price in wei
+    /// * `nonce` - Transaction nonce
+    ///
+    #[inline]
+    pub async fn sell_token_min_funds_signed(
+        &self,
+        token: Address,
+        amount: U256,
+        min_funds: U256,
+        gas_price: u128,
+        nonce: u64,
+    ) -> Result<SignedTransaction, Error> {
+        let sell_tx = self
+            .sell_token_min_funds_request(token, amount, min_funds, gas_price)
+            .with_nonce(nonce);
+
+        self.sender.sign(sell_tx).await
+    }
+
+    #[inline]
+    fn sell_token_min_funds_request(
+        &self,
+        token: Address,
+        amount: U256,
+        min_funds: U256,
+        gas_price: u128,
+    ) -> TransactionRequest {
+        TransactionRequest::default()
+            .with_to(FOURMEME_CONTRACT)
+            .with_value(U256::ZERO)
+            .with_gas_limit(200000_u64)
+            .with_gas_price(gas_price)
+            .with_input(
+                FourMemeContractCalls::sellToken_1(sellToken_1Call {
+                    origin: U256::ZERO,
+                    token,
+                    amount,
+                    minFunds: min_funds,
+                    feeRate: U256::ZERO,
+                    feeRecipient: Address::ZERO,
+                })
+                .abi_encode(),
+            )
+    }
+}
+
+#[tokio::test]
+async fn test_create_token_request() {
+    use alloy::{primitives::Bytes, sol_types::SolInterface};
+    use async_trait::async_trait;
+
+    struct MockSource;
+
+    #[async_trait]
+    impl CreatePayloadSource for MockSource {
+        async fn create_payload(
+            &self,
+            _creator: &Sender,
+            metadata: &TokenMetadata,
+        ) -> Result<CreatePayload, Error> {
+            Ok(CreatePayload {
+                args: Bytes::from(metadata.symbol.clone().into_bytes()),
+                signature: Bytes::from(vec![0x1b; 65]),
+            })
+        }
+    }
+
+    let sender = Sender::new(
+        "0x0123456789012345678901234567890123456789012345678901234567890123",
+        56,
+    )
+    .unwrap();
+    let metadata = TokenMetadata::new("Test", "TEST").with_pre_buy(U256::from(1_000));
+    let payload = MockSource.create_payload(&sender, &metadata).await.unwrap();
+
+    let tx = FourMeme::create_token_request(payload, metadata.pre_buy, 1_000_000_000);
+    assert_eq!(tx.value, Some(U256::from(1_000)));
+    let input = tx.input.input().unwrap();
+    match FourMemeContractCalls::abi_decode(input).unwrap() {
+        FourMemeContractCalls::createToken(call) => {
+            assert_eq!(call.args, Bytes::from_static(b"TEST"));
+            assert_eq!(call.signature.len(), 65);
+        }
+        _ => panic!("unexpected call"),
+    }
+}
+
+#[test]
+fn test_buy_token_to_request() {
+    use alloy::sol_types::SolCall;
+
+    let recipient = Address::repeat_byte(0x11);
+    let token = Address::repeat_byte(0x22);
+
+    let tx = FourMeme::buy_token_to_request(recipient, U256::from(1_000), token, U256::from(5), 1);
+    let input = tx.input.input().unwrap();
+    let call = buyTokenAMAP_2Call::abi_decode(input).unwrap();
+    assert_eq!((call.token, call.to), (token, recipient));
+    assert_eq!(
+        (call.funds, call.minAmount),
+        (U256::from(1_000), U256::from(5))
+    );
+    assert_eq!(tx.value, Some(U256::from(1_000)));
+
+    let tx =
+        FourMeme::buy_token_exact_to_request(recipient, token, U256::from(5), U256::from(9), 1);
+    let call = buyToken_2Call::abi_decode(tx.input.input().unwrap()).unwrap();
+    assert_eq!((call.token, call.to), (token, recipient));
+    assert_eq!((call.amount, call.maxFunds), (U256::from(5), U256::from(9)));
+    assert_eq!(tx.value, Some(U256::from(9)));
+}
+
+#[tokio::test]
+async fn test_token_infos_allow_failure() {
+    use abi::FourMemeHelper::getTokenInfoCall;
+    use alloy::{
+        primitives::Bytes,
+        providers::{ProviderBuilder, bindings::IMulticall3, mock::Asserter},
+        sol_types::SolCall,
+    };
+
+    let asserter = Asserter::new();
+    let provider = ProviderBuilder::new()
+        .connect_mocked_client(asserter.clone())
+        .erased();
+    let sender = Sender::new(
+        "0x0123456789012345678901234567890123456789012345678901234567890123",
+        56,
+    )
+    .unwrap();
+    let fourmeme = FourMeme {
+        client: Arc::new(provider),
+        sender,
+    };
+
+    let known = Address::repeat_byte(0x11);
+    let unknown = Address::repeat_byte(0x22);
+    let helper = getTokenInfoReturn {
+        version: U256::from(2),
+        tokenManager: FOURMEME_CONTRACT,
+        quote: Address::ZERO,
+        lastPrice: U256::from(5),
+        tradingFeeRate: U256::from(100),
+        minTradingFee: U256::ZERO,
+        launchTime: U256::from(1_700_000_000u64),
+        offers: U256::from(600),
+        maxOffers: U256::from(800),
+        funds: U256::from(3),
+        maxFunds: U256::from(24),
+        liquidityAdded: false,
+    };
+    let manager = _tokenInfosReturn {
+        base: known,
+        quote: Address::ZERO,
+        template: U256::ZERO,
+        totalSupply: U256::from(1_000),
+        maxOffers: helper.maxOffers,
+        maxRaising: helper.maxFunds,
+        launchTime: helper.launchTime,
+        offers: helper.offers,
+        funds: helper.funds,
+        lastPrice: helper.lastPrice,
+        K: U256::ZERO,
+        T: U256::ZERO,
+        status: U256::ZERO,
+    };
+    let aggregate = |results: Vec<(bool, Vec<u8>)>| {
+        let results: Vec<IMulticall3::Result> = results
+            .into_iter()
+            .map(|(success, data)| IMulticall3::Result {
+                success,
+                returnData: Bytes::from(data),
+            })
+            .collect();
+        Bytes::from(IMulticall3::aggregate3Call::abi_encode_returns(&results))
+    };
+
+    // The helper reverts for the unknown token only
+    asserter.push_success(&aggregate(vec![
+        (true, getTokenInfoCall::abi_encode_returns(&helper)),
+        (false, Vec::new()),
+    ]));
+    asserter.push_success(&aggregate(vec![
+        (true, _tokenInfosCall::abi_encode_returns(&manager)),
+        (true, _tokenInfosCall::abi_encode_returns(&manager)),
+    ]));
+    asserter.push_success(&aggregate(vec![
+        (
+            true,
+            _tokenCreatorsCall::abi_encode_returns(&Address::repeat_byte(0x33)),
+        ),
+        (false, Vec::new()),
+    ]));
+
+    let infos = fourmeme.token_infos(&[known, unknown]).await.unwrap();
+    assert_eq!(infos.len(), 2);
+    let info = infos[0].as_ref().unwrap();
+    assert_eq!(info.creator, Some(Address::repeat_byte(0x33)));
+    assert_eq!(info.progress_bps(), 2_500);
+    assert!(infos[1].is_err());
 }
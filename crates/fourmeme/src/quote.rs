@@ -0,0 +1,352 @@
+use abi::FourMemeContract::_tokenInfosReturn;
+use alloy::primitives::U256;
+pub use pancake_v2::quote::apply_slippage;
+
+use crate::{
+    constants::{
+        BPS_DENOMINATOR, CURVE_MAX_FUNDS, CURVE_MAX_OFFERS, CURVE_VIRTUAL_FUNDS,
+        CURVE_VIRTUAL_OFFERS, TRADE_FEE_BPS,
+    },
+    parser::{TokenPurchase, TokenSale},
+};
+
+/// Bonding curve state of a token, as reported by the latest trade event
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CurveState {
+    /// Tokens still offered on the curve
+    pub offers: U256,
+    /// BNB raised by the curve so far
+    pub funds: U256,
+}
+
+impl From<&TokenPurchase> for CurveState {
+    fn from(purchase: &TokenPurchase) -> Self {
+        Self {
+            offers: purchase.offers,
+            funds: purchase.funds,
+        }
+    }
+}
+
+impl From<&TokenSale> for CurveState {
+    fn from(sale: &TokenSale) -> Self {
+        Self {
+            offers: sale.offers,
+            funds: sale.funds,
+        }
+    }
+}
+
+/// Constant-product bonding curve over virtual reserves
+///
+/// The defaults follow the standard BNB launch: 800M tokens offered for a 24 BNB raise.
+/// Tokens launched from another template or against another quote have a curve of their
+/// own, see `from_token_manager`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BondingCurve {
+    pub virtual_funds: U256,
+    pub virtual_offers: U256,
+    /// Tokens offered before the liquidity migrates to PancakeSwap
+    pub max_offers: U256,
+    /// Funds raised once every offered token is sold
+    pub max_funds: U256,
+    pub fee_bps: u16,
+}
+
+impl Default for BondingCurve {
+    fn default() -> Self {
+        Self {
+            virtual_funds: CURVE_VIRTUAL_FUNDS,
+            virtual_offers: CURVE_VIRTUAL_OFFERS,
+            max_offers: CURVE_MAX_OFFERS,
+            max_funds: CURVE_MAX_FUNDS,
+            fee_bps: TRADE_FEE_BPS,
+        }
+    }
+}
+
+impl BondingCurve {
+    /// Curve of a token from its token manager record, `_tokenInfos`
+    ///
+    /// `T` is the token reserve at launch, virtual offers included, and `K` the constant
+    /// product of the reserves. Returns `None` when they are unset or disagree with
+    /// `maxOffers` and `maxRaising`, e.g. for a token unknown to the token manager.
+    #[inline]
+    pub fn from_token_manager(info: &_tokenInfosReturn) -> Option<Self> {
+        if info.T <= info.maxOffers || info.maxRaising.is_zero() {
+            return None;
+        }
+        let virtual_offers = info.T - info.maxOffers;
+        let virtual_funds = info.K / info.T;
+        if virtual_funds.is_zero() {
+            return None;
+        }
+
+        // Selling every offered token must raise `maxRaising`, up to rounding
+        let raised = (info.K / virtual_offers).saturating_sub(virtual_funds);
+        if raised.abs_diff(info.maxRaising) > info.maxRaising / U256::from(100) {
+            return None;
+        }
+
+        Some(Self {
+            virtual_funds,
+            virtual_offers,
+            max_offers: info.maxOffers,
+            max_funds: info.maxRaising,
+            fee_bps: TRADE_FEE_BPS,
+        })
+    }
+
+    /// Expected amount of tokens bought with `funds` wei at the given curve state
+    ///
+    /// The trading fee is deducted from `funds` first and the result is capped at the
+    /// remaining offers.
+    #[inline]
+    pub fn quote_buy(&self, state: &CurveState, funds: U256) -> U256 {
+        let funds_in =
+            funds * U256::from(BPS_DENOMINATOR - self.fee_bps) / U256::from(BPS_DENOMINATOR);
+        if funds_in.is_zero() {
+            return U256::ZERO;
+        }
+
+        let reserve_offers = state.offers + self.virtual_offers;
+        let reserve_funds = state.funds + self.virtual_funds;
+        let amount = reserve_offers * funds_in / (reserve_funds + funds_in);
+
+        amount.min(state.offers)
+    }
+
+    /// Funds in wei, fee included, needed to buy exactly `amount` tokens at the given curve state
+    ///
+    /// Returns `None` when the curve does not offer that many tokens.
+    #[inline]
+    pub fn quote_buy_exact(&self, state: &CurveState, amount: U256) -> Option<U256> {
+        if amount.is_zero() {
+            return Some(U256::ZERO);
+        }
+        if amount > state.offers {
+            return None;
+        }
+
+        let reserve_offers = state.offers + self.virtual_offers;
+        let reserve_funds = state.funds + self.virtual_funds;
+        let funds_in = (reserve_funds * amount).div_ceil(reserve_offers - amount);
+
+        Some(
+            (funds_in * U256::from(BPS_DENOMINATOR))
+                .div_ceil(U256::from(BPS_DENOMINATOR - self.fee_bps)),
+        )
+    }
+
+    /// Expected funds in wei, fee deducted, received for selling `amount` tokens at the given
+    /// curve state
+    ///
+    /// The result is capped at the funds raised by the curve.
+    #[inline]
+    pub fn quote_sell(&self, state: &CurveState, amount: U256) -> U256 {
+        if amount.is_zero() {
+            return U256::ZERO;
+        }
+
+        let reserve_offers = state.offers + self.virtual_offers;
+        let reserve_funds = state.funds + self.virtual_funds;
+        let funds_out = (reserve_funds * amount / (reserve_offers + amount)).min(state.funds);
+
+        funds_out * U256::from(BPS_DENOMINATOR - self.fee_bps) / U256::from(BPS_DENOMINATOR)
+    }
+
+    /// Share of the offered tokens already sold, in basis points
+    #[inline]
+    pub fn progress_bps(&self, state: &CurveState) -> u16 {
+        progress_bps(self.max_offers, state.offers)
+    }
+
+    /// BNB still to be raised before the curve graduates
+    #[inline]
+    pub fn funds_to_graduate(&self, state: &CurveState) -> U256 {
+        self.max_funds.saturating_sub(state.funds)
+    }
+}
+
+/// Bonding curve of a token with its state, everything a curve quote needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenCurve {
+    pub curve: BondingCurve,
+    pub state: CurveState,
+}
+
+impl TokenCurve {
+    /// Curve and current state of a token from its token manager record, see
+    /// `BondingCurve::from_token_manager`
+    #[inline]
+    pub fn from_token_manager(info: &_tokenInfosReturn) -> Option<Self> {
+        Some(Self {
+            curve: BondingCurve::from_token_manager(info)?,
+            state: CurveState {
+                offers: info.offers,
+                funds: info.funds,
+            },
+        })
+    }
+
+    /// Same curve at a newer state, e.g. from the latest trade event
+    #[inline]
+    pub fn with_state(mut self, state: CurveState) -> Self {
+        self.state = state;
+        self
+    }
+}
+
+/// Share of `max_offers` already sold when `offers` tokens are left, in basis points
+#[inline]
+pub fn progress_bps(max_offers: U256, offers: U256) -> u16 {
+    if max_offers.is_zero() {
+        return 0;
+    }
+    let sold = max_offers.saturating_sub(offers);
+    let progress = sold * U256::from(BPS_DENOMINATOR) / max_offers;
+    progress.saturating_to::<u16>().min(BPS_DENOMINATOR)
+}
+
+/// Thresholds crossed upwards when progress moves from `previous_bps` to `current_bps`
+#[inline]
+pub fn crossed_thresholds(previous_bps: u16, current_bps: u16, thresholds: &[u16]) -> Vec<u16> {
+    thresholds
+        .iter()
+        .copied()
+        .filter(|threshold| previous_bps < *threshold && *threshold <= current_bps)
+        .collect()
+}
+
+/// Increase `amount` by a slippage tolerance in basis points
+#[inline]
+pub fn add_slippage(amount: U256, slippage_bps: u16) -> U256 {
+    amount * U256::from(BPS_DENOMINATOR + slippage_bps.min(BPS_DENOMINATOR))
+        / U256::from(BPS_DENOMINATOR)
+}
+
+#[test]
+fn test_quote_buy() {
+    let curve = BondingCurve::default();
+    let state = CurveState {
+        offers: U256::from(800_000_000u64) * U256::from(10u64).pow(U256::from(18)),
+        funds: U256::ZERO,
+    };
+    let one_bnb = U256::from(10u64).pow(U256::from(18));
+
+    let amount = curve.quote_buy(&state, one_bnb);
+    assert!(amount > U256::ZERO && amount < state.offers);
+    // more funds always buy more tokens, but at a worse average price
+    let amount_double = curve.quote_buy(&state, one_bnb * U256::from(2));
+    assert!(amount_double > amount && amount_double < amount * U256::from(2));
+
+    assert_eq!(apply_slippage(amount, 0), amount);
+    assert_eq!(apply_slippage(amount, 10_000), U256::ZERO);
+    assert_eq!(apply_slippage(U256::from(10_000), 150), U256::from(9_850));
+}
+
+#[test]
+fn test_quote_exact_and_sell() {
+    let curve = BondingCurve::default();
+    let one_bnb = U256::from(10u64).pow(U256::from(18));
+    let state = CurveState {
+        offers: U256::from(700_000_000u64) * one_bnb,
+        funds: U256::from(3u64) * one_bnb,
+    };
+
+    // buying the quoted amount for a fixed spend never costs more than that spend
+    let amount = curve.quote_buy(&state, one_bnb);
+    let cost = curve.quote_buy_exact(&state, amount).unwrap();
+    assert!(cost <= one_bnb && cost > one_bnb * U256::from(99) / U256::from(100));
+    assert_eq!(
+        curve.quote_buy_exact(&state, state.offers + U256::from(1)),
+        None
+    );
+
+    // selling right back returns less than was spent because of the fees
+    let after_buy = CurveState {
+        offers: state.offers - amount,
+        funds: state.funds + one_bnb * U256::from(99) / U256::from(100),
+    };
+    let proceeds = curve.quote_sell(&after_buy, amount);
+    assert!(proceeds > U256::ZERO && proceeds < one_bnb);
+
+    assert_eq!(add_slippage(U256::from(10_000), 150), U256::from(10_150));
+}
+
+#[test]
+fn test_curve_progress() {
+    let curve = BondingCurve::default();
+    let one_token = U256::from(10u64).pow(U256::from(18));
+    let state = CurveState {
+        offers: U256::from(200_000_000u64) * one_token,
+        funds: U256::from(15u64) * one_token,
+    };
+
+    assert_eq!(curve.progress_bps(&state), 7_500);
+    assert_eq!(
+        curve.funds_to_graduate(&state),
+        U256::from(9u64) * one_token
+    );
+    assert_eq!(curve.progress_bps(&CurveState::default()), 10_000);
+
+    let thresholds = [5_000, 8_000, 9_000];
+    assert_eq!(
+        crossed_thresholds(4_000, 8_500, &thresholds),
+        vec![5_000, 8_000]
+    );
+    assert!(crossed_thresholds(8_500, 7_000, &thresholds).is_empty());
+    assert_eq!(crossed_thresholds(8_999, 9_000, &thresholds), vec![9_000]);
+}
+
+#[test]
+fn test_curve_from_token_manager() {
+    use alloy::primitives::Address;
+
+    let one_token = U256::from(10u64).pow(U256::from(18));
+    // A USD1 curve: 700M tokens offered for a 12,000 USD1 raise
+    let max_offers = U256::from(700_000_000u64) * one_token;
+    let max_raising = U256::from(12_000u64) * one_token;
+    let virtual_offers = U256::from(350_000_000u64) * one_token;
+    let virtual_funds = U256::from(6_000u64) * one_token;
+    let total = max_offers + virtual_offers;
+    let mut info = _tokenInfosReturn {
+        base: Address::repeat_byte(0x11),
+        quote: Address::repeat_byte(0x22),
+        template: U256::from(1),
+        totalSupply: U256::from(1_000_000_000u64) * one_token,
+        maxOffers: max_offers,
+        maxRaising: max_raising,
+        launchTime: U256::ZERO,
+        offers: max_offers,
+        funds: U256::ZERO,
+        lastPrice: U256::ZERO,
+        K: total * virtual_funds,
+        T: total,
+        status: U256::ZERO,
+    };
+
+    let curve = BondingCurve::from_token_manager(&info).unwrap();
+    assert_eq!(
+        (curve.virtual_offers, curve.virtual_funds),
+        (virtual_offers, virtual_funds)
+    );
+    assert_eq!(
+        (curve.max_offers, curve.max_funds),
+        (max_offers, max_raising)
+    );
+
+    // The default curve would quote far more tokens for the same spend
+    let state = CurveState {
+        offers: max_offers,
+        funds: U256::ZERO,
+    };
+    let hundred = U256::from(100u64) * one_token;
+    assert!(curve.quote_buy(&state, hundred) < BondingCurve::default().quote_buy(&state, hundred));
+
+    // Unknown tokens and inconsistent records have no curve
+    info.maxRaising = max_raising * U256::from(2);
+    assert_eq!(BondingCurve::from_token_manager(&info), None);
+    info.T = U256::ZERO;
+    assert_eq!(BondingCurve::from_token_manager(&info), None);
+}
@@ -1,4 +1,4 @@
-use alloy::primitives::{b256, B256};
+use alloy::primitives::{B256, b256};
 
 pub const SWAP_TOPIC: B256 =
     b256!("0xd78ad95fa46c994b6551d0da85fc275fe613ce37657fb8d5e3d130840159d822");
@@ -6,3 +6,13 @@ pub const SYNC_TOPIC: B256 =
     b256!("0x1c411e9a96e071241c2f21f7726b17ae89e3cab4c78be50e062b03a9fffbbad1");
 pub const PAIR_CREATED_TOPIC: B256 =
     b256!("0x0d3648bd0f6ba80134a33ba9275ac585d9d315f0ad8355cddefde31afa28d0e9");
+/// Init code hash of the V2 pair, used to derive pair addresses with CREATE2
+pub const PAIR_INIT_CODE_HASH: B256 =
+    b256!("0x00fb7f630766e6a796048ea87d01acd3068e8ff67d078148a3fa3f4a84f69bd5");
+
+/// Swap fee numerator (0.25% fee)
+pub const FEE_NUMERATOR: u64 = 9975;
+/// Swap fee denominator
+pub const FEE_DENOMINATOR: u64 = 10_000;
+/// Basis points denominator
+pub const BPS_DENOMINATOR: u16 = 10_000;
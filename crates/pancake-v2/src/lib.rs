@@ -1,26 +1,33 @@
 pub mod constants;
+pub mod pair;
 pub mod parser;
+pub mod quote;
 
 use abi::IERC20::{IERC20Calls, approveCall};
 use alloy::{
-    consensus::{SignableTransaction, TxEnvelope, TypedTransaction},
-    eips::Encodable2718,
-    hex,
-    network::{TransactionBuilder, TxSigner},
+    network::TransactionBuilder,
     primitives::{Address, U256, address},
     providers::{DynProvider, Provider},
     rpc::types::{TransactionInput, TransactionReceipt, TransactionRequest},
-    signers::local::PrivateKeySigner,
     sol,
     sol_types::{SolCall, SolInterface},
 };
 use anyhow::Error;
-use rpc::Rpc;
+use rpc::{Rpc, wallet::Wallet};
+use sender::{Sender, SignedTransaction};
 use std::sync::Arc;
 
+use crate::{
+    parser::PancakeSwapPair,
+    quote::{apply_slippage, get_amount_out},
+};
+
 /// PancakeSwap Router
 pub const PANCAKESWAP_ROUTER: Address = address!("0x10ED43C718714eb63d5aA57B78B54704E256024E");
 
+/// PancakeSwap V2 Factory
+pub const PANCAKESWAP_FACTORY: Address = address!("0xcA143Ce32Fe78f1f7019d7d551a6402fC5350c73");
+
 /// WBNB
 pub const WBNB: Address = address!("0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c");
 
@@ -43,6 +50,11 @@ sol! {
             address to,
             uint256 deadline
         ) external;
+
+        function getAmountsOut(
+            uint256 amountIn,
+            address[] calldata path
+        ) external view returns (uint256[] memory amounts);
     }
 }
 
@@ -50,26 +62,121 @@ sol! {
 pub struct Pancake {
     client: Arc<DynProvider>,
     receiver: Address,
-    signer: PrivateKeySigner,
+    sender: Sender,
 }
 
 impl Pancake {
-    pub async fn init(rpc: Rpc, signer: PrivateKeySigner) -> Result<Self, Error> {
+    pub async fn init(rpc: Rpc, sender: Sender) -> Result<Self, Error> {
+        if sender.chain_id() != rpc.chain_id {
+            return Err(Error::msg(format!(
+                "Sender chain id {} does not match rpc chain id {}",
+                sender.chain_id(),
+                rpc.chain_id
+            )));
+        }
         let client = Arc::new(rpc.client);
-        let receiver = rpc.sender_address;
+        // Swaps are sent from and paid out to the signing wallet, whichever of the pool it is
+        let receiver = sender.address();
         Ok(Self {
             client,
             receiver,
-            signer,
+            sender,
         })
     }
 
+    /// Trade from another wallet of the pool, sharing the same provider
+    ///
+    /// # Arguments
+    ///
+    /// * `wallet` - The wallet to sign and send from, e.g. `rpc.wallets.next()`
+    #[inline]
+    pub fn for_wallet(&self, wallet: &Wallet) -> Self {
+        Self {
+            client: Arc::clone(&self.client),
+            receiver: wallet.address(),
+            sender: wallet.sender.clone(),
+        }
+    }
+
+    /// Quote the output amounts along `path` with the router's `getAmountsOut`
+    ///
+    /// # Arguments
+    ///
+    /// * `amount_in` - The amount of the first token in `path`
+    /// * `path` - The swap path
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<U256>` - The amount at every hop, the last one being the output
+    #[inline]
+    pub async fn get_amounts_out(
+        &self,
+        amount_in: U256,
+        path: Vec<Address>,
+    ) -> Result<Vec<U256>, Error> {
+        let amounts = PancakeSwapRouter::new(PANCAKESWAP_ROUTER, &self.client)
+            .getAmountsOut(amount_in, path)
+            .call()
+            .await?;
+        Ok(amounts)
+    }
+
+    /// Quote a single-hop swap offline from the reserves of `pair`
+    ///
+    /// # Arguments
+    ///
+    /// * `pair` - The pair address
+    /// * `token_in` - The token being sold into the pair
+    /// * `amount_in` - The amount of `token_in`
+    #[inline]
+    pub async fn quote_from_pair(
+        &self,
+        pair: Address,
+        token_in: Address,
+        amount_in: U256,
+    ) -> Result<U256, Error> {
+        let pair_contract = PancakeSwapPair::new(pair, &self.client);
+        let token0 = pair_contract.token0().call().await?;
+        let reserves = pair_contract.getReserves().call().await?;
+        let reserve0 = U256::from(reserves.reserve0);
+        let reserve1 = U256::from(reserves.reserve1);
+
+        let amount_out = if token_in == token0 {
+            get_amount_out(amount_in, reserve0, reserve1)
+        } else {
+            get_amount_out(amount_in, reserve1, reserve0)
+        };
+        Ok(amount_out)
+    }
+
+    /// Minimum output accepted for swapping `amount_in` along `path`
+    #[inline]
+    async fn min_amount_out(
+        &self,
+        amount_in: U256,
+        path: Vec<Address>,
+        slippage_bps: u16,
+    ) -> Result<U256, Error> {
+        let amounts = self.get_amounts_out(amount_in, path).await?;
+        // Never fall back to amountOutMin = 0, which would disable slippage protection
+        match amounts.last() {
+            Some(amount_out) if !amount_out.is_zero() => {
+                Ok(apply_slippage(*amount_out, slippage_bps))
+            }
+            _ => Err(Error::msg(format!(
+                "getAmountsOut returned no output for {} in: {:?}",
+                amount_in, amounts
+            ))),
+        }
+    }
+
     /// Use ether to buy tokens
     ///
     /// # Arguments
     ///
     /// * `token` - The address of the token to buy
     /// * `ether_spent` - The amount of ether to spend
+    /// * `slippage_bps` - The slippage tolerance in basis points
     ///
     /// # Returns
     ///
@@ -79,8 +186,12 @@ impl Pancake {
         &self,
         token: Address,
         ether_spent: U256,
+        slippage_bps: u16,
     ) -> Result<TransactionReceipt, Error> {
         let path = vec![WBNB, token];
+        let amount_out_min = self
+            .min_amount_out(ether_spent, path.clone(), slippage_bps)
+            .await?;
 
         let deadline = U256::from(
             std::time::SystemTime::now()
@@ -89,8 +200,9 @@ impl Pancake {
                 + 300,
         );
         let pending_tx = PancakeSwapRouter::new(PANCAKESWAP_ROUTER, &self.client)
-            .swapExactETHForTokens(U256::ZERO, path, self.receiver, deadline)
+            .swapExactETHForTokens(amount_out_min, path, self.receiver, deadline)
             .value(ether_spent)
+            .from(self.receiver)
             .send()
             .await?;
 
@@ -99,33 +211,32 @@ impl Pancake {
     }
 
     /// Use ether to buy tokens with a signed transaction
-    /// This function returns a signed transaction hex string without submitting it
+    /// This function returns a signed transaction without submitting it
     ///
     /// # Arguments
     ///
     /// * `token` - The address of the token to buy
     /// * `ether_spent` - The amount of ether to spend
+    /// * `slippage_bps` - The slippage tolerance in basis points
     /// * `gas_price` - Gas price in wei
     /// * `nonce` - Transaction nonce
     ///
     /// # Returns
     ///
-    /// * `String` - The signed transaction hex string
+    /// * `SignedTransaction` - The raw signed transaction and its hash
     #[inline]
     pub async fn swap_exact_ethfor_tokens_signed(
         &self,
         token: Address,
         ether_spent: U256,
+        slippage_bps: u16,
         gas_price: u128,
         nonce: u64,
-    ) -> Result<String, Error> {
-        // Get sender address and chain_id for EIP-155 replay protection
-        let sender_address = self.signer.address();
-        let chain_id = self.client.get_chain_id().await?;
-
-        let signer = self.signer.clone();
-
+    ) -> Result<SignedTransaction, Error> {
         let path = vec![WBNB, token];
+        let amount_out_min = self
+            .min_amount_out(ether_spent, path.clone(), slippage_bps)
+            .await?;
         let deadline = U256::from(
             std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)?
@@ -135,7 +246,6 @@ impl Pancake {
 
         // Build the transaction using ABI encoding
         let swap_tx = TransactionRequest::default()
-            .from(sender_address)
             .to(PANCAKESWAP_ROUTER)
             .value(ether_spent)
             .gas_limit(300000_u64)
@@ -143,45 +253,16 @@ impl Pancake {
             .nonce(nonce)
             .input(TransactionInput::new(
                 PancakeSwapRouter::swapExactETHForTokensCall {
-                    amountOutMin: U256::ZERO,
-                    path: path,
+                    amountOutMin: amount_out_min,
+                    path,
                     to: self.receiver,
-                    deadline: deadline,
+                    deadline,
                 }
                 .abi_encode()
                 .into(),
             ));
 
-        let typed_tx = swap_tx
-            .build_typed_tx()
-            .map_err(|e| Error::msg(format!("Failed to build typed transaction: {:?}", e)))?;
-
-        let signed_envelope: TxEnvelope = match typed_tx {
-            TypedTransaction::Legacy(mut tx) => {
-                tx.chain_id = Some(chain_id);
-                let sig = signer
-                    .sign_transaction(&mut tx)
-                    .await
-                    .map_err(|e| Error::msg(format!("Failed to sign transaction: {:?}", e)))?;
-                tx.into_signed(sig).into()
-            }
-            TypedTransaction::Eip1559(mut tx) => {
-                tx.chain_id = chain_id;
-                let sig = signer
-                    .sign_transaction(&mut tx)
-                    .await
-                    .map_err(|e| Error::msg(format!("Failed to sign transaction: {:?}", e)))?;
-                tx.into_signed(sig).into()
-            }
-            _ => {
-                return Err(Error::msg("Unsupported transaction type"));
-            }
-        };
-
-        let raw_tx_bytes: Vec<u8> = signed_envelope.encoded_2718();
-        let raw_tx_hex = hex::encode(&raw_tx_bytes);
-
-        Ok(raw_tx_hex)
+        self.sender.sign(swap_tx).await
     }
 
     /// Sell tokens for ether
@@ -190,6 +271,7 @@ impl Pancake {
     ///
     /// * `token` - The address of the token to sell
     /// * `tokens_spent` - The amount of tokens to sell
+    /// * `slippage_bps` - The slippage tolerance in basis points
     ///
     /// # Returns
     ///
@@ -199,9 +281,13 @@ impl Pancake {
         &self,
         token: Address,
         tokens_spent: U256,
+        slippage_bps: u16,
     ) -> Result<TransactionReceipt, Error> {
         let gas_price = self.client.get_gas_price().await?;
         let path = vec![token, WBNB];
+        let amount_out_min = self
+            .min_amount_out(tokens_spent, path.clone(), slippage_bps)
+            .await?;
         let deadline = U256::from(
             std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)?
@@ -209,8 +295,9 @@ impl Pancake {
                 + 300,
         );
         let pending_tx = PancakeSwapRouter::new(PANCAKESWAP_ROUTER, &self.client)
-            .swapExactTokensForETH(tokens_spent, U256::ZERO, path, self.receiver, deadline)
+            .swapExactTokensForETH(tokens_spent, amount_out_min, path, self.receiver, deadline)
             .gas_price(gas_price)
+            .from(self.receiver)
             .send()
             .await?;
 
@@ -218,19 +305,72 @@ impl Pancake {
         Ok(receipt)
     }
 
+    /// Sell tokens for ether with a signed transaction
+    /// This function returns a signed transaction without submitting it
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token to sell
+    /// * `tokens_spent` - The amount of tokens to sell
+    /// * `slippage_bps` - The slippage tolerance in basis points
+    /// * `gas_price` - Gas price in wei
+    /// * `nonce` - Transaction nonce
+    ///
+    /// # Returns
+    ///
+    /// * `SignedTransaction` - The raw signed transaction and its hash
+    #[inline]
+    pub async fn swap_exact_tokensfor_eth_signed(
+        &self,
+        token: Address,
+        tokens_spent: U256,
+        slippage_bps: u16,
+        gas_price: u128,
+        nonce: u64,
+    ) -> Result<SignedTransaction, Error> {
+        let path = vec![token, WBNB];
+        let amount_out_min = self
+            .min_amount_out(tokens_spent, path.clone(), slippage_bps)
+            .await?;
+        let deadline = U256::from(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs()
+                + 300,
+        );
+
+        let swap_tx = TransactionRequest::default()
+            .to(PANCAKESWAP_ROUTER)
+            .gas_limit(300000_u64)
+            .gas_price(gas_price)
+            .nonce(nonce)
+            .input(TransactionInput::new(
+                PancakeSwapRouter::swapExactTokensForETHCall {
+                    amountIn: tokens_spent,
+                    amountOutMin: amount_out_min,
+                    path,
+                    to: self.receiver,
+                    deadline,
+                }
+                .abi_encode()
+                .into(),
+            ));
+
+        self.sender.sign(swap_tx).await
+    }
+
     /// Approve the pancake swap router to spend the token
     #[inline]
     pub async fn approve_token(&self, token: Address) -> Result<TransactionReceipt, Error> {
         let gas_price = self.client.get_gas_price().await?;
         let approve_tx = TransactionRequest::default()
+            .with_from(self.receiver)
             .with_to(token)
             .with_input(
-                IERC20Calls::approve {
-                    0: approveCall {
-                        spender: PANCAKESWAP_ROUTER,
-                        allowance: U256::MAX,
-                    },
-                }
+                IERC20Calls::approve(approveCall {
+                    spender: PANCAKESWAP_ROUTER,
+                    allowance: U256::MAX,
+                })
                 .abi_encode(),
             )
             .with_gas_price(gas_price);
@@ -238,4 +378,68 @@ impl Pancake {
         let receipt = pending_tx.get_receipt().await?;
         Ok(receipt)
     }
+
+    /// Approve the pancake swap router to spend the token with a signed transaction
+    /// This function returns a signed transaction without submitting it
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The address of the token to approve
+    /// * `gas_price` - Gas price in wei
+    /// * `nonce` - Transaction nonce
+    ///
+    /// # Returns
+    ///
+    /// * `SignedTransaction` - The raw signed transaction and its hash
+    #[inline]
+    pub async fn approve_token_signed(
+        &self,
+        token: Address,
+        gas_price: u128,
+        nonce: u64,
+    ) -> Result<SignedTransaction, Error> {
+        let approve_tx = TransactionRequest::default()
+            .to(token)
+            .gas_limit(100000_u64)
+            .gas_price(gas_price)
+            .nonce(nonce)
+            .input(TransactionInput::new(
+                IERC20Calls::approve(approveCall {
+                    spender: PANCAKESWAP_ROUTER,
+                    allowance: U256::MAX,
+                })
+                .abi_encode()
+                .into(),
+            ));
+
+        self.sender.sign(approve_tx).await
+    }
+}
+
+#[tokio::test]
+async fn test_init_receives_at_the_sender() {
+    use alloy::providers::{ProviderBuilder, mock::Asserter};
+    use rpc::wallet::WalletPool;
+
+    let keys = [
+        "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
+    ];
+    let wallets = WalletPool::new(WalletPool::parse_keys(&keys).unwrap(), 56).unwrap();
+    let primary = wallets.primary();
+    let other = wallets.by_index(1).unwrap();
+    let rpc = Rpc {
+        client: ProviderBuilder::new()
+            .connect_mocked_client(Asserter::new())
+            .erased(),
+        sender_address: primary.address(),
+        chain_id: 56,
+        gas_price: Arc::new(tokio::sync::Mutex::new(0)),
+        nonce: Arc::clone(&primary.nonce),
+        wallets: Arc::new(wallets),
+    };
+
+    let pancake = Pancake::init(rpc, other.sender.clone()).await.unwrap();
+    assert_eq!(pancake.receiver, other.address());
+    assert_eq!(pancake.sender.address(), other.address());
 }
@@ -0,0 +1,34 @@
+use alloy::primitives::{Address, keccak256};
+
+use crate::{PANCAKESWAP_FACTORY, constants::PAIR_INIT_CODE_HASH};
+
+/// Order two tokens the way the factory does, `token0` first
+#[inline]
+pub fn sort_tokens(token_a: Address, token_b: Address) -> (Address, Address) {
+    if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    }
+}
+
+/// Address of the V2 pair of two tokens, whether or not it has been created yet
+#[inline]
+pub fn pair_address(token_a: Address, token_b: Address) -> Address {
+    let (token0, token1) = sort_tokens(token_a, token_b);
+    let salt = keccak256([token0.as_slice(), token1.as_slice()].concat());
+    PANCAKESWAP_FACTORY.create2(salt, PAIR_INIT_CODE_HASH)
+}
+
+#[test]
+fn test_pair_address() {
+    use alloy::primitives::address;
+
+    let busd = address!("0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56");
+    let wbnb = crate::WBNB;
+
+    let pair = address!("0x58F876857a02D6762E0101bb5C46A8c1ED44Dc16");
+    assert_eq!(pair_address(wbnb, busd), pair);
+    assert_eq!(pair_address(busd, wbnb), pair);
+    assert_eq!(sort_tokens(busd, wbnb), (wbnb, busd));
+}
@@ -0,0 +1,47 @@
+use alloy::primitives::U256;
+
+use crate::constants::{BPS_DENOMINATOR, FEE_DENOMINATOR, FEE_NUMERATOR};
+
+/// Constant-product output for `amount_in`, mirroring `PancakeLibrary.getAmountOut`
+///
+/// # Arguments
+///
+/// * `amount_in` - The amount of the input token
+/// * `reserve_in` - The pair reserve of the input token
+/// * `reserve_out` - The pair reserve of the output token
+///
+#[inline]
+pub fn get_amount_out(amount_in: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+    if amount_in.is_zero() || reserve_in.is_zero() || reserve_out.is_zero() {
+        return U256::ZERO;
+    }
+
+    let amount_in_with_fee = amount_in * U256::from(FEE_NUMERATOR);
+    let numerator = amount_in_with_fee * reserve_out;
+    let denominator = reserve_in * U256::from(FEE_DENOMINATOR) + amount_in_with_fee;
+    numerator / denominator
+}
+
+/// Reduce `amount` by a slippage tolerance in basis points
+#[inline]
+pub fn apply_slippage(amount: U256, slippage_bps: u16) -> U256 {
+    let slippage_bps = slippage_bps.min(BPS_DENOMINATOR);
+    amount * U256::from(BPS_DENOMINATOR - slippage_bps) / U256::from(BPS_DENOMINATOR)
+}
+
+#[test]
+fn test_get_amount_out() {
+    // 1 BNB into a 100 BNB / 1M token pool
+    let amount_in = U256::from(10u64).pow(U256::from(18));
+    let reserve_in = amount_in * U256::from(100);
+    let reserve_out = amount_in * U256::from(1_000_000);
+
+    let amount_out = get_amount_out(amount_in, reserve_in, reserve_out);
+    // 1e18 * 9975 * 1e24 / (1e20 * 10000 + 1e18 * 9975)
+    assert_eq!(amount_out, U256::from(9_876_482_091_140_869_823_510u128));
+    assert_eq!(
+        get_amount_out(amount_in, U256::ZERO, reserve_out),
+        U256::ZERO
+    );
+    assert_eq!(apply_slippage(U256::from(10_000), 50), U256::from(9_950));
+}
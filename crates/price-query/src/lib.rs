@@ -1,49 +1,385 @@
-use alloy::primitives::Address;
+pub mod subscriber;
+
+use alloy::primitives::{Address, U256};
 use anyhow::Error;
+use iceoryx2::pending_response::PendingResponse;
 use iceoryx2::port::client::Client;
 use iceoryx2::{node::NodeBuilder, service::ipc};
-use types::{PriceRequest, PriceResponse, RequestType};
+use std::time::Duration;
+use tokio::time::{Instant, sleep};
+pub use types::{MAX_BATCH_SIZE, PriceSource, SUBSCRIPTION_BUFFER_SIZE, ServiceNames, TokenStatus};
+use types::{MAX_SUBSCRIPTIONS, PriceRequest, PriceResponse, RequestType};
+
+/// How long a query waits for the tracker to close its request
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Delay between two polls of the tracker
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+type Pending = PendingResponse<ipc::Service, PriceRequest, (), PriceResponse, ()>;
+type PriceClient = Client<ipc::Service, PriceRequest, (), PriceResponse, ()>;
+
+/// Price snapshot of a token as served by the tracker, zero when unknown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriceInfo {
+    pub token: Address,
+    pub status: TokenStatus,
+    /// Venue of the latest price
+    pub source: PriceSource,
+    /// Quote token of the token, zero for BNB
+    pub quote: Address,
+    /// Pancake pair of the token, zero while on the curve
+    pub pair: Address,
+    /// BNB wei per token scaled by 1e18
+    pub wei_per_token: U256,
+    /// USD per token scaled by 1e18
+    pub usd_per_token: U256,
+    /// USD market cap scaled by 1e18
+    pub usd_market_cap: U256,
+    /// Block of the latest price update
+    pub block_number: u64,
+    /// Timestamp of the latest price update, in seconds
+    pub timestamp: u64,
+    /// Pair reserve of the token, or tokens left on the curve
+    pub reserve_token: U256,
+    /// Pair reserve of the quote, or funds raised by the curve
+    pub reserve_quote: U256,
+    /// Curve progress in basis points, 10_000 once migrated
+    pub progress_bps: u16,
+}
+
+impl From<&PriceResponse> for PriceInfo {
+    fn from(response: &PriceResponse) -> Self {
+        Self {
+            token: Address::from(response.token_address),
+            status: response.status,
+            source: response.source,
+            quote: Address::from(response.quote_token),
+            pair: Address::from(response.pair_address),
+            wei_per_token: U256::from_be_bytes(response.wei_per_token),
+            usd_per_token: U256::from_be_bytes(response.usd_per_token),
+            usd_market_cap: U256::from_be_bytes(response.usd_market_cap),
+            block_number: response.block_number,
+            timestamp: response.timestamp,
+            reserve_token: U256::from_be_bytes(response.reserve_token),
+            reserve_quote: U256::from_be_bytes(response.reserve_quote),
+            progress_bps: response.progress_bps,
+        }
+    }
+}
+
+/// Pancake pair of a token as served by the tracker
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PairInfo {
+    pub token: Address,
+    pub pair: Address,
+    /// Quote token of the pair, WBNB for BNB
+    pub quote: Address,
+    /// Whether the token is token0 of the pair
+    pub is_token0: bool,
+    /// Pair reserve of the token, zero until the reserves are read
+    pub reserve_token: U256,
+    /// Pair reserve of the quote, zero until the reserves are read
+    pub reserve_quote: U256,
+    /// Block of the latest reserves
+    pub block_number: u64,
+    /// Timestamp of the latest reserves, in seconds
+    pub timestamp: u64,
+}
+
+impl From<&PriceResponse> for PairInfo {
+    fn from(response: &PriceResponse) -> Self {
+        Self {
+            token: Address::from(response.token_address),
+            pair: Address::from(response.pair_address),
+            quote: Address::from(response.quote_token),
+            is_token0: response.is_token0,
+            reserve_token: U256::from_be_bytes(response.reserve_token),
+            reserve_quote: U256::from_be_bytes(response.reserve_quote),
+            block_number: response.block_number,
+            timestamp: response.timestamp,
+        }
+    }
+}
 
 pub struct PriceQuery {
-    client: Client<ipc::Service, PriceRequest, (), PriceResponse, ()>,
+    client: PriceClient,
+    subscription_client: PriceClient,
 }
 
 impl PriceQuery {
     pub async fn init() -> Result<Self, Error> {
+        Self::init_with_services(&ServiceNames::default()).await
+    }
+
+    /// Connect to a tracker serving the given IPC services
+    pub async fn init_with_services(services: &ServiceNames) -> Result<Self, Error> {
         let node = NodeBuilder::new().create::<ipc::Service>()?;
 
         let service = node
-            .service_builder(&"token_price_query".try_into()?)
+            .service_builder(&services.query.as_str().try_into()?)
             .request_response::<PriceRequest, PriceResponse>()
+            .max_response_buffer_size(MAX_BATCH_SIZE)
             .open_or_create()?;
 
         let client = service.client_builder().create()?;
-        Ok(Self { client })
+
+        let subscription_service = node
+            .service_builder(&services.subscriptions.as_str().try_into()?)
+            .request_response::<PriceRequest, PriceResponse>()
+            .max_response_buffer_size(SUBSCRIPTION_BUFFER_SIZE)
+            .enable_safe_overflow_for_responses(true)
+            .max_active_requests_per_client(MAX_SUBSCRIPTIONS + 1)
+            .open_or_create()?;
+
+        let subscription_client = subscription_service.client_builder().create()?;
+        Ok(Self {
+            client,
+            subscription_client,
+        })
     }
 
     #[inline]
-    pub async fn query_price(&self, token_address: Address) -> Result<u128, Error> {
+    pub async fn query_price(&self, token_address: Address) -> Result<U256, Error> {
+        self.request_one(RequestType::GetPrice, token_address)
+            .await
+            .map(|info| info.wei_per_token)
+    }
+
+    /// Query the price snapshot of a token: prices, venue, reserves and curve progress
+    #[inline]
+    pub async fn query_price_info(&self, token_address: Address) -> Result<PriceInfo, Error> {
+        self.request_one(RequestType::GetPrice, token_address).await
+    }
+
+    #[inline]
+    pub async fn remove_token(&self, token_address: Address) -> Result<(), Error> {
+        self.request_one(RequestType::RemoveToken, token_address)
+            .await
+            .map(|_| ())
+    }
+
+    /// Start tracking a token, from its Pancake pair when it already holds liquidity
+    #[inline]
+    pub async fn add_token(&self, token_address: Address) -> Result<PriceInfo, Error> {
+        self.request_one(RequestType::AddToken, token_address).await
+    }
+
+    /// List up to `MAX_BATCH_SIZE` tracked tokens, sorted by address, from `offset`
+    #[inline]
+    pub async fn list_tokens(&self, offset: u32) -> Result<Vec<PriceInfo>, Error> {
+        let request = PriceRequest {
+            request_type: RequestType::ListTokens,
+            offset,
+            ..Default::default()
+        };
+        receive_all(&self.client.send_copy(request)?).await
+    }
+
+    /// Query the Pancake pair a token is priced from, `None` while on the curve
+    #[inline]
+    pub async fn get_pair(&self, token_address: Address) -> Result<Option<PairInfo>, Error> {
         let request = PriceRequest {
-            request_type: RequestType::GetPrice,
+            request_type: RequestType::GetPair,
             token_address: token_address.0.0,
+            ..Default::default()
         };
-        let response = self.client.send_copy(request)?;
-        match response.receive()? {
-            Some(response) => Ok(response.payload().wei_per_token),
-            None => Err(Error::msg("No response received")),
+        let pair = receive_all::<PairInfo>(&self.client.send_copy(request)?)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::msg("No response received"))?;
+        Ok(Some(pair).filter(|pair| !pair.pair.is_zero()))
+    }
+
+    /// Query the price snapshots of up to `MAX_BATCH_SIZE` tokens in one request
+    #[inline]
+    pub async fn query_batch_prices(
+        &self,
+        token_addresses: &[Address],
+    ) -> Result<Vec<PriceInfo>, Error> {
+        if token_addresses.len() > MAX_BATCH_SIZE {
+            return Err(Error::msg(format!(
+                "Batch of {} tokens exceeds the maximum of {}",
+                token_addresses.len(),
+                MAX_BATCH_SIZE
+            )));
         }
+
+        let mut request = PriceRequest {
+            request_type: RequestType::GetBatchPrices,
+            token_count: token_addresses.len() as u32,
+            ..Default::default()
+        };
+        for (slot, token_address) in request.token_addresses.iter_mut().zip(token_addresses) {
+            *slot = token_address.0.0;
+        }
+        receive_all(&self.client.send_copy(request)?).await
     }
 
+    /// Subscribe to the price changes of a token
+    ///
+    /// The first update is the current snapshot. Up to `SUBSCRIPTION_BUFFER_SIZE` updates
+    /// are buffered; when they are not read fast enough the oldest one is overwritten, so
+    /// a slow reader skips updates rather than losing the subscription.
+    ///
+    /// The subscription ends on `unsubscribe`, when it is dropped, or when the tracker fails
+    /// to deliver an update to it (the tracker logs it), after which `next` returns `None`.
     #[inline]
-    pub async fn remove_token(&self, token_address: Address) -> Result<(), Error> {
+    pub fn subscribe(&self, token_address: Address) -> Result<PriceSubscription, Error> {
         let request = PriceRequest {
-            request_type: RequestType::RemoveToken,
+            request_type: RequestType::Subscribe,
             token_address: token_address.0.0,
+            ..Default::default()
         };
-        let response = self.client.send_copy(request)?;
-        match response.receive()? {
-            Some(_) => Ok(()),
-            None => Err(Error::msg("No response received")),
+        Ok(PriceSubscription {
+            pending: self.subscription_client.send_copy(request)?,
+        })
+    }
+
+    /// Close every subscription of this client to a token
+    #[inline]
+    pub async fn unsubscribe(&self, token_address: Address) -> Result<(), Error> {
+        // Sent on the subscription service, the tracker matches it to the subscribing client
+        let request = PriceRequest {
+            request_type: RequestType::Unsubscribe,
+            token_address: token_address.0.0,
+            ..Default::default()
+        };
+        receive_all::<PriceInfo>(&self.subscription_client.send_copy(request)?)
+            .await
+            .map(|_| ())
+    }
+
+    /// Send a single-token request and wait for its response
+    #[inline]
+    async fn request_one(
+        &self,
+        request_type: RequestType,
+        token_address: Address,
+    ) -> Result<PriceInfo, Error> {
+        let request = PriceRequest {
+            request_type,
+            token_address: token_address.0.0,
+            ..Default::default()
+        };
+        receive_all(&self.client.send_copy(request)?)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::msg("No response received"))
+    }
+}
+
+/// Price updates of a subscribed token
+pub struct PriceSubscription {
+    pending: Pending,
+}
+
+impl PriceSubscription {
+    /// Next buffered update, `None` when there is none yet
+    #[inline]
+    pub fn try_next(&self) -> Result<Option<PriceInfo>, Error> {
+        Ok(self
+            .pending
+            .receive()?
+            .map(|response| PriceInfo::from(response.payload())))
+    }
+
+    /// Wait for the next update, `None` once the tracker closed the subscription
+    ///
+    /// See `PriceQuery::subscribe` for when updates are skipped or the subscription closes.
+    #[inline]
+    pub async fn next(&self) -> Result<Option<PriceInfo>, Error> {
+        loop {
+            let connected = self.pending.is_connected();
+            if let Some(info) = self.try_next()? {
+                return Ok(Some(info));
+            }
+            if !connected {
+                return Ok(None);
+            }
+            sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Whether the tracker still sends updates
+    #[inline]
+    pub fn is_connected(&self) -> bool {
+        self.pending.is_connected()
+    }
+}
+
+/// Collect the responses to a request until the tracker closes it
+#[inline]
+async fn receive_all<T: for<'a> From<&'a PriceResponse>>(
+    pending: &Pending,
+) -> Result<Vec<T>, Error> {
+    let deadline = Instant::now() + RESPONSE_TIMEOUT;
+    let mut responses = Vec::new();
+    loop {
+        // Read the state first, every response sent before closing is then buffered
+        let connected = pending.is_connected();
+        while let Some(response) = pending.receive()? {
+            responses.push(T::from(response.payload()));
+        }
+        if !connected {
+            return Ok(responses);
+        }
+        if Instant::now() >= deadline {
+            return Err(Error::msg("Timed out waiting for the price tracker"));
         }
+        sleep(POLL_INTERVAL).await;
     }
 }
+
+#[tokio::test]
+async fn test_query_waits_for_the_tracker() {
+    use iceoryx2::port::server::Server;
+
+    let services = ServiceNames::with_prefix(&format!("test_price_query_{}", std::process::id()));
+    let query = PriceQuery::init_with_services(&services).await.unwrap();
+
+    let node = NodeBuilder::new().create::<ipc::Service>().unwrap();
+    let server: Server<ipc::Service, PriceRequest, (), PriceResponse, ()> = node
+        .service_builder(&services.query.as_str().try_into().unwrap())
+        .request_response::<PriceRequest, PriceResponse>()
+        .max_response_buffer_size(MAX_BATCH_SIZE)
+        .open_or_create()
+        .unwrap()
+        .server_builder()
+        .create()
+        .unwrap();
+
+    // The tracker only answers on a later iteration of its event loop
+    let tracker = async {
+        for _ in 0..3 {
+            let request = loop {
+                sleep(Duration::from_millis(20)).await;
+                if let Some(request) = server.receive().unwrap() {
+                    break request;
+                }
+            };
+            let status = match request.payload().request_type {
+                RequestType::RemoveToken => TokenStatus::Removed,
+                _ => TokenStatus::Tracked,
+            };
+            request
+                .send_copy(PriceResponse {
+                    token_address: request.payload().token_address,
+                    status,
+                    wei_per_token: U256::from(7u64).to_be_bytes(),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+    };
+
+    let token = Address::repeat_byte(0x22);
+    let queries = async {
+        let info = query.query_price_info(token).await.unwrap();
+        assert_eq!((info.token, info.status), (token, TokenStatus::Tracked));
+        assert_eq!(query.query_price(token).await.unwrap(), U256::from(7u64));
+        query.remove_token(token).await.unwrap();
+    };
+    tokio::join!(tracker, queries);
+}
@@ -0,0 +1,181 @@
+use alloy::primitives::{Address, U256};
+use anyhow::Error;
+use futures_util::{Stream, stream};
+use iceoryx2::port::{listener::Listener, subscriber::Subscriber};
+use iceoryx2::{node::NodeBuilder, service::ipc};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use types::{PriceSource, PriceTick, ServiceNames, UPDATE_BUFFER_SIZE};
+
+use crate::POLL_INTERVAL;
+
+/// Price change published by the tracker
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriceChange {
+    pub token: Address,
+    /// Venue of the new price
+    pub source: PriceSource,
+    /// BNB wei per token scaled by 1e18
+    pub wei_per_token: U256,
+    /// USD per token scaled by 1e18
+    pub usd_per_token: U256,
+    pub block_number: u64,
+    /// Timestamp of the change, in seconds
+    pub timestamp: u64,
+}
+
+impl From<&PriceTick> for PriceChange {
+    fn from(tick: &PriceTick) -> Self {
+        Self {
+            token: Address::from(tick.token_address),
+            source: tick.source,
+            wei_per_token: U256::from_be_bytes(tick.wei_per_token),
+            usd_per_token: U256::from_be_bytes(tick.usd_per_token),
+            block_number: tick.block_number,
+            timestamp: tick.timestamp,
+        }
+    }
+}
+
+/// Receives every price change published by the tracker, without polling each token
+pub struct PriceSubscriber {
+    subscriber: Subscriber<ipc::Service, PriceTick, ()>,
+    listener: Listener<ipc::Service>,
+    poll_interval: Duration,
+}
+
+impl PriceSubscriber {
+    pub async fn init() -> Result<Self, Error> {
+        Self::init_with_services(&ServiceNames::default()).await
+    }
+
+    /// Listen to a tracker publishing on the given IPC services
+    pub async fn init_with_services(services: &ServiceNames) -> Result<Self, Error> {
+        let node = NodeBuilder::new().create::<ipc::Service>()?;
+
+        let service = node
+            .service_builder(&services.updates.as_str().try_into()?)
+            .publish_subscribe::<PriceTick>()
+            .subscriber_max_buffer_size(UPDATE_BUFFER_SIZE)
+            .open_or_create()?;
+
+        let subscriber = service
+            .subscriber_builder()
+            .buffer_size(UPDATE_BUFFER_SIZE)
+            .create()?;
+
+        let update_events = node
+            .service_builder(&services.update_events.as_str().try_into()?)
+            .event()
+            .open_or_create()?;
+
+        let listener = update_events.listener_builder().create()?;
+        Ok(Self {
+            subscriber,
+            listener,
+            poll_interval: POLL_INTERVAL,
+        })
+    }
+
+    /// Override the delay between two polls of `recv` and `into_stream`, 1 ms by default
+    #[inline]
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Next buffered price change, `None` when there is none yet
+    #[inline]
+    pub fn try_recv(&self) -> Result<Option<PriceChange>, Error> {
+        Ok(self
+            .subscriber
+            .receive()?
+            .map(|sample| PriceChange::from(sample.payload())))
+    }
+
+    /// Wait for the next price change, polling every `poll_interval`
+    #[inline]
+    pub async fn recv(&self) -> Result<PriceChange, Error> {
+        loop {
+            if let Some(change) = self.try_recv()? {
+                return Ok(change);
+            }
+            sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Block the thread until the next price change, `None` after `timeout`
+    ///
+    /// Woken by the tracker as soon as it publishes instead of polling, for consumers that
+    /// run on a thread of their own.
+    #[inline]
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Option<PriceChange>, Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            // A tick published after this read also notifies, so the wait below returns
+            if let Some(change) = self.try_recv()? {
+                return Ok(Some(change));
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || self.listener.timed_wait_one(remaining)?.is_none() {
+                return self.try_recv();
+            }
+        }
+    }
+
+    /// Turn the subscriber into an endless stream of price changes
+    #[inline]
+    pub fn into_stream(self) -> impl Stream<Item = Result<PriceChange, Error>> {
+        stream::unfold(self, |subscriber| async move {
+            let change = subscriber.recv().await;
+            Some((change, subscriber))
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_recv_timeout_wakes_on_publish() {
+    let services =
+        ServiceNames::with_prefix(&format!("test_price_subscriber_{}", std::process::id()));
+    let subscriber = PriceSubscriber::init_with_services(&services)
+        .await
+        .unwrap();
+
+    // Stand-in for the tracker: publish a tick, then notify
+    let node = NodeBuilder::new().create::<ipc::Service>().unwrap();
+    let publisher = node
+        .service_builder(&services.updates.as_str().try_into().unwrap())
+        .publish_subscribe::<PriceTick>()
+        .subscriber_max_buffer_size(UPDATE_BUFFER_SIZE)
+        .open_or_create()
+        .unwrap()
+        .publisher_builder()
+        .create()
+        .unwrap();
+    let notifier = node
+        .service_builder(&services.update_events.as_str().try_into().unwrap())
+        .event()
+        .open_or_create()
+        .unwrap()
+        .notifier_builder()
+        .create()
+        .unwrap();
+
+    let timeout = Duration::from_millis(10);
+    assert_eq!(subscriber.recv_timeout(timeout).unwrap(), None);
+
+    let token = Address::repeat_byte(0x33);
+    publisher
+        .send_copy(PriceTick {
+            token_address: token.0.0,
+            source: PriceSource::FourmemeCurve,
+            block_number: 1,
+            ..Default::default()
+        })
+        .unwrap();
+    notifier.notify().unwrap();
+
+    let change = subscriber.recv_timeout(timeout).unwrap().unwrap();
+    assert_eq!((change.token, change.block_number), (token, 1));
+    assert_eq!(subscriber.recv_timeout(timeout).unwrap(), None);
+}
@@ -0,0 +1,73 @@
+use alloy::primitives::{Address, U256};
+use fourmeme::quote::{BondingCurve, CurveState, crossed_thresholds};
+
+/// Default progress thresholds, in basis points of the offered tokens sold
+pub const DEFAULT_PROGRESS_THRESHOLDS: [u16; 4] = [5_000, 8_000, 9_000, 9_500];
+
+/// Bonding curve progress of a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveProgress {
+    pub token: Address,
+    /// Quote asset of the curve, `BNB_ADDRESS` for BNB
+    pub quote: Address,
+    pub state: CurveState,
+    /// Share of the offered tokens already sold, in basis points
+    pub progress_bps: u16,
+    /// Quote still to be raised before the liquidity migrates, in its smallest unit
+    pub funds_remaining: U256,
+}
+
+/// Curve events emitted by the tracker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveEvent {
+    /// Progress crossed `threshold_bps` upwards; a sale followed by new buys crosses it again
+    ThresholdCrossed {
+        threshold_bps: u16,
+        progress: CurveProgress,
+    },
+    /// Liquidity was added to PancakeSwap
+    Graduated { token: Address, quote: Address },
+}
+
+/// Thresholds crossed when the curve state of a token moves from `previous` to `current`
+///
+/// Nothing is crossed without a previous state: the progress made before the token was
+/// first seen is unknown, so thresholds it already passed are not reported.
+#[inline]
+pub fn thresholds_crossed(
+    curve: &BondingCurve,
+    previous: Option<&CurveState>,
+    current: &CurveState,
+    thresholds: &[u16],
+) -> Vec<u16> {
+    let Some(previous) = previous else {
+        return Vec::new();
+    };
+    crossed_thresholds(
+        curve.progress_bps(previous),
+        curve.progress_bps(current),
+        thresholds,
+    )
+}
+
+#[test]
+fn test_thresholds_crossed() {
+    let curve = BondingCurve::default();
+    let at_bps = |bps: u64| CurveState {
+        offers: curve.max_offers - curve.max_offers * U256::from(bps) / U256::from(10_000u64),
+        funds: U256::ZERO,
+    };
+
+    // A token first seen at 85% has no threshold to report
+    let first = at_bps(8_500);
+    assert!(thresholds_crossed(&curve, None, &first, &DEFAULT_PROGRESS_THRESHOLDS).is_empty());
+
+    let next = at_bps(9_200);
+    assert_eq!(
+        thresholds_crossed(&curve, Some(&first), &next, &DEFAULT_PROGRESS_THRESHOLDS),
+        vec![9_000]
+    );
+    assert!(
+        thresholds_crossed(&curve, Some(&next), &first, &DEFAULT_PROGRESS_THRESHOLDS).is_empty()
+    );
+}
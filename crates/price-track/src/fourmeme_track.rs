@@ -1,7 +1,10 @@
 use alloy::{eips::BlockNumberOrTag, primitives::Log, providers::Provider, rpc::types::Filter};
 use anyhow::Error;
 use fourmeme::{
-    constants::{FOURMEME_CONTRACT, TOKEN_CREATE_TOPIC, TOKEN_PURCHASE_TOPIC, TOKEN_SALE_TOPIC},
+    constants::{
+        FOURMEME_CONTRACT, LIQUIDITY_ADDED_TOPIC, TOKEN_CREATE_TOPIC, TOKEN_PURCHASE_TOPIC,
+        TOKEN_SALE_TOPIC,
+    },
     parser::{FourmemeEvent, parse_fourmeme_event_by_topic},
 };
 use futures_util::StreamExt;
@@ -9,13 +12,15 @@ use rpc::Rpc;
 use tokio::sync::mpsc;
 use tracing::{error, info};
 
+use crate::snapshot::EventMeta;
+
 pub struct FourmemeTrack {
     rpc: Rpc,
-    tx: mpsc::UnboundedSender<FourmemeEvent>,
+    tx: mpsc::UnboundedSender<(FourmemeEvent, EventMeta)>,
 }
 
 impl FourmemeTrack {
-    pub fn new(rpc: Rpc, tx: mpsc::UnboundedSender<FourmemeEvent>) -> Self {
+    pub fn new(rpc: Rpc, tx: mpsc::UnboundedSender<(FourmemeEvent, EventMeta)>) -> Self {
         Self { rpc, tx }
     }
 
@@ -28,6 +33,7 @@ impl FourmemeTrack {
                 TOKEN_PURCHASE_TOPIC,
                 TOKEN_SALE_TOPIC,
                 TOKEN_CREATE_TOPIC,
+                LIQUIDITY_ADDED_TOPIC,
             ]);
 
         let sub = self.rpc.client.subscribe_logs(&filter).await?;
@@ -36,6 +42,7 @@ impl FourmemeTrack {
         info!("FourmemeTrack started listening to events");
 
         while let Some(log) = stream.next().await {
+            let meta = EventMeta::from_log(&log);
             let Some(log) = Log::new(log.address(), log.topics().to_vec(), log.data().data.clone()) else {
                 error!("Failed to create Log");
                 continue;
@@ -47,7 +54,7 @@ impl FourmemeTrack {
             };
 
             // Send event through channel
-            if let Err(e) = self.tx.send(event) {
+            if let Err(e) = self.tx.send((event, meta)) {
                 error!(?e, "Failed to send event through channel");
                 break;
             }
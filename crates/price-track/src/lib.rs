@@ -1,52 +1,244 @@
+pub mod curve;
 mod fourmeme_track;
+mod lookup;
+pub mod metadata;
 mod pancake_track;
+pub mod quotes;
+pub mod snapshot;
 
-use alloy::primitives::{Address, address};
+use alloy::primitives::{Address, U256};
 use anyhow::Error;
-use dashmap::DashMap;
-use fourmeme::parser::FourmemeEvent;
-use iceoryx2::{node::NodeBuilder, port::server::Server, service::ipc};
-use pancake_v2::parser::PancakeSwapEvent;
+use dashmap::{DashMap, DashSet};
+use fourmeme::{
+    parser::FourmemeEvent,
+    quote::{BondingCurve, CurveState},
+};
+use iceoryx2::{
+    active_request::ActiveRequest,
+    node::NodeBuilder,
+    port::{notifier::Notifier, publisher::Publisher, server::Server},
+    service::ipc,
+};
+use pancake_v2::{WBNB, pair::pair_address, parser::PancakeSwapEvent};
 use rpc::Rpc;
-use tokio::{signal, sync::mpsc::unbounded_channel};
-use tracing::info;
-use types::{PriceRequest, PriceResponse, RequestType};
+use std::{
+    sync::{Mutex, RwLock},
+    time::{Duration, Instant},
+};
+use tokio::{
+    signal,
+    sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+    time::interval,
+};
+use tracing::{info, warn};
+use types::{
+    MAX_BATCH_SIZE, MAX_SUBSCRIPTIONS, PriceRequest, PriceResponse, PriceSource, PriceTick,
+    RequestType, SUBSCRIPTION_BUFFER_SIZE, ServiceNames, TokenStatus, UPDATE_BUFFER_SIZE,
+};
 
-use crate::{fourmeme_track::FourmemeTrack, pancake_track::PancakeTrack};
+use crate::{
+    curve::{CurveEvent, CurveProgress, DEFAULT_PROGRESS_THRESHOLDS, thresholds_crossed},
+    fourmeme_track::FourmemeTrack,
+    lookup::{Backoff, ChainlinkRound, CurveInfo, Discovery, Found, Lookup, LookupResult},
+    metadata::{DEFAULT_DECIMALS, TokenMetadata},
+    pancake_track::PancakeTrack,
+    quotes::{
+        BNB_ADDRESS, DEFAULT_CHAINLINK_HEARTBEAT, KNOWN_QUOTES, PRICE_SCALE, QuoteRate, USDT,
+        div_scaled, is_stable, mul_div, mul_scaled, pow10, price_from_reserves,
+    },
+    snapshot::{EventMeta, PairSnapshot, PriceSnapshot, PriceUpdate},
+};
+
+/// Request answered after its handler returns: a subscription or a pending `AddToken`
+type OpenRequest = ActiveRequest<ipc::Service, PriceRequest, (), PriceResponse, ()>;
 
-const BNB_ADDRESS: Address = address!("0x0000000000000000000000000000000000000000");
 pub struct PriceTrack {
     rpc: Rpc,
-    tokens: DashMap<Address, u128>, // <token address, wei per token>
+    tokens: DashMap<Address, U256>, // <token address, quote per whole token, 1e18 scaled>
     pairs: DashMap<Address, (Address, bool)>, // <pair address, (token address, is_token0)>
+    token_pairs: DashMap<Address, Address>, // <token address, pair address>, reverse of `pairs`
+    token_quotes: DashMap<Address, Address>, // <token address, quote asset>, BNB when absent
+    quote_pairs: DashMap<Address, (Address, bool)>, // <WBNB pair address, (quote, is_token0)>
+    quote_rates: DashMap<Address, U256>, // <quote asset, wei per whole quote token>
+    metadata: DashMap<Address, TokenMetadata>, // <token or quote address, ERC-20 metadata>
+    updates: DashMap<Address, PriceUpdate>, // <token address, venue of the latest price>
+    migrated: DashSet<Address>,
+    removed: DashSet<Address>,
+    supplies: DashMap<Address, U256>, // <token address, total supply in wei>
+    bnb_usd_quote: Address,
+    chainlink_feed: Option<Address>,
+    chainlink_interval: Duration,
+    chainlink_heartbeat: Duration,
+    chainlink_usd_per_bnb: RwLock<Option<(U256, u64)>>, // <(USD per BNB, updated at)>
+    curves: DashMap<Address, CurveState>,               // <token address, latest curve state>
+    token_curves: DashMap<Address, BondingCurve>, // <token address, curve read from the token manager>
+    curve: BondingCurve,
+    progress_thresholds: Vec<u16>,
+    curve_events: Option<UnboundedSender<CurveEvent>>,
+    subscriptions: Mutex<Vec<(Address, OpenRequest)>>, // <(token address, open request)>
+    subscribed_tokens: DashSet<Address>,               // tokens with at least one open subscription
+    pending_adds: Mutex<Vec<(Address, OpenRequest)>>,  // <(token address, AddToken request)>
+    lookup_tx: UnboundedSender<LookupResult>,
+    lookup_rx: Mutex<Option<UnboundedReceiver<LookupResult>>>, // taken by `start`
+    lookups_in_flight: DashSet<Lookup>,
+    failed_lookups: DashMap<Lookup, Backoff>, // <lookup, negative cache entry>
     ipc_server: Server<ipc::Service, PriceRequest, (), PriceResponse, ()>,
+    subscription_server: Server<ipc::Service, PriceRequest, (), PriceResponse, ()>,
+    publisher: Publisher<ipc::Service, PriceTick, ()>,
+    notifier: Notifier<ipc::Service>, // wakes the subscribers waiting for a tick
 }
 
 impl PriceTrack {
     /// Initialize PriceTrack
     pub async fn init(rpc: Rpc) -> Result<Self, Error> {
+        Self::init_with_services(rpc, &ServiceNames::default()).await
+    }
+
+    /// Initialize PriceTrack serving the given IPC services, e.g. to run beside another tracker
+    pub async fn init_with_services(rpc: Rpc, services: &ServiceNames) -> Result<Self, Error> {
         let node = NodeBuilder::new().create::<ipc::Service>()?;
 
         let service = node
-            .service_builder(&"token_price_query".try_into()?)
+            .service_builder(&services.query.as_str().try_into()?)
             .request_response::<PriceRequest, PriceResponse>()
+            .max_response_buffer_size(MAX_BATCH_SIZE)
             .open_or_create()?;
 
         let ipc_server = service.server_builder().create()?;
 
+        // Subscriptions get their own service so their responses can be buffered longer
+        let subscription_service = node
+            .service_builder(&services.subscriptions.as_str().try_into()?)
+            .request_response::<PriceRequest, PriceResponse>()
+            .max_response_buffer_size(SUBSCRIPTION_BUFFER_SIZE)
+            .enable_safe_overflow_for_responses(true)
+            .max_active_requests_per_client(MAX_SUBSCRIPTIONS + 1)
+            .open_or_create()?;
+
+        let subscription_server = subscription_service.server_builder().create()?;
+
+        let update_service = node
+            .service_builder(&services.updates.as_str().try_into()?)
+            .publish_subscribe::<PriceTick>()
+            .subscriber_max_buffer_size(UPDATE_BUFFER_SIZE)
+            .open_or_create()?;
+
+        let publisher = update_service.publisher_builder().create()?;
+
+        let update_events = node
+            .service_builder(&services.update_events.as_str().try_into()?)
+            .event()
+            .open_or_create()?;
+
+        let notifier = update_events.notifier_builder().create()?;
+
+        let quote_pairs = DashMap::new();
+        for quote in KNOWN_QUOTES {
+            quote_pairs.insert(pair_address(quote, WBNB), (quote, quote < WBNB));
+        }
+
+        let (lookup_tx, lookup_rx) = unbounded_channel();
+
         Ok(Self {
             rpc,
             tokens: DashMap::new(),
             pairs: DashMap::new(),
+            token_pairs: DashMap::new(),
+            token_quotes: DashMap::new(),
+            quote_pairs,
+            quote_rates: DashMap::new(),
+            metadata: DashMap::new(),
+            updates: DashMap::new(),
+            migrated: DashSet::new(),
+            removed: DashSet::new(),
+            supplies: DashMap::new(),
+            bnb_usd_quote: USDT,
+            chainlink_feed: None,
+            chainlink_interval: Duration::from_secs(30),
+            chainlink_heartbeat: DEFAULT_CHAINLINK_HEARTBEAT,
+            chainlink_usd_per_bnb: RwLock::new(None),
+            curves: DashMap::new(),
+            token_curves: DashMap::new(),
+            curve: BondingCurve::default(),
+            progress_thresholds: DEFAULT_PROGRESS_THRESHOLDS.to_vec(),
+            curve_events: None,
+            subscriptions: Mutex::new(Vec::new()),
+            subscribed_tokens: DashSet::new(),
+            pending_adds: Mutex::new(Vec::new()),
+            lookup_tx,
+            lookup_rx: Mutex::new(Some(lookup_rx)),
+            lookups_in_flight: DashSet::new(),
+            failed_lookups: DashMap::new(),
             ipc_server,
+            subscription_server,
+            publisher,
+            notifier,
         })
     }
 
+    /// Override the progress thresholds, in basis points, that emit `CurveEvent::ThresholdCrossed`
+    #[inline]
+    pub fn with_progress_thresholds(mut self, thresholds: Vec<u16>) -> Self {
+        self.progress_thresholds = thresholds;
+        self
+    }
+
+    /// Override the bonding curve used to compute the progress of a token until its own
+    /// curve is read from the token manager
+    #[inline]
+    pub fn with_curve(mut self, curve: BondingCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Price BNB in USD from the WBNB pair of another stable quote, e.g. `BUSD`
+    #[inline]
+    pub fn with_bnb_usd_quote(mut self, stable: Address) -> Self {
+        self.quote_pairs
+            .insert(pair_address(stable, WBNB), (stable, stable < WBNB));
+        self.bnb_usd_quote = stable;
+        self
+    }
+
+    /// Price BNB in USD from a Chainlink aggregator, polled every `interval`
+    ///
+    /// The pair reference is still used until the first answer is read, and whenever the
+    /// answer is older than the heartbeat.
+    #[inline]
+    pub fn with_chainlink_feed(mut self, aggregator: Address, interval: Duration) -> Self {
+        self.chainlink_feed = Some(aggregator);
+        self.chainlink_interval = interval;
+        self
+    }
+
+    /// Override the age past which a Chainlink answer is stale, 5 minutes by default
+    #[inline]
+    pub fn with_chainlink_heartbeat(mut self, heartbeat: Duration) -> Self {
+        self.chainlink_heartbeat = heartbeat;
+        self
+    }
+
+    /// Receive curve threshold and graduation events, replacing any previous receiver
+    #[inline]
+    pub fn subscribe_curve_events(&mut self) -> UnboundedReceiver<CurveEvent> {
+        let (curve_tx, curve_rx) = unbounded_channel();
+        self.curve_events = Some(curve_tx);
+        curve_rx
+    }
+
     /// Start event listener    
     #[inline]
     pub async fn start(&self) -> Result<(), Error> {
-        let (fourmeme_tx, mut fourmeme_rx) = unbounded_channel::<FourmemeEvent>();
-        let (pancake_tx, mut pancake_rx) = unbounded_channel::<(PancakeSwapEvent, Address)>();
+        let (fourmeme_tx, mut fourmeme_rx) = unbounded_channel::<(FourmemeEvent, EventMeta)>();
+        let (pancake_tx, mut pancake_rx) =
+            unbounded_channel::<(PancakeSwapEvent, Address, EventMeta)>();
+
+        let mut lookup_rx = self
+            .lookup_rx
+            .lock()
+            .ok()
+            .and_then(|mut lookup_rx| lookup_rx.take())
+            .ok_or_else(|| Error::msg("PriceTrack is already started"))?;
 
         let fourmeme_rpc = self.rpc.clone();
         let pancake_rpc = self.rpc.clone();
@@ -67,15 +259,24 @@ impl PriceTrack {
             }
         });
 
+        self.seed_quote_rates();
+        let mut chainlink_ticker = interval(self.chainlink_interval);
+
         info!("PriceTrack started, waiting for events...");
 
         loop {
             tokio::select! {
-                Some(event) = fourmeme_rx.recv() => {
-                    self.handle_fourmeme_event(event);
+                _ = chainlink_ticker.tick(), if self.chainlink_feed.is_some() => {
+                    self.refresh_chainlink();
                 }
-                Some((event, pair_address)) = pancake_rx.recv() => {
-                    self.handle_pancake_event(event, pair_address);
+                Some((event, meta)) = fourmeme_rx.recv() => {
+                    self.handle_fourmeme_event(event, meta);
+                }
+                Some((event, pair_address, meta)) = pancake_rx.recv() => {
+                    self.handle_pancake_event(event, pair_address, meta);
+                }
+                Some((lookup, found)) = lookup_rx.recv() => {
+                    self.apply_lookup(lookup, found);
                 }
                 _ = signal::ctrl_c() => {
                     tracing::info!("Received Ctrl+C, shutting down...");
@@ -93,114 +294,343 @@ impl PriceTrack {
         Ok(())
     }
 
-    /// Handle one IPC request (non-blocking check)
+    /// Handle one IPC request of each service (non-blocking check)
     #[inline]
     fn handle_ipc_request(&self) -> Result<(), Error> {
-        let ipc_server = &self.ipc_server;
-
-        if let Some(active_request) = ipc_server.receive()? {
-            // Extract token address
-            let token_address = Address::from(active_request.payload().token_address);
-
-            match active_request.payload().request_type {
-                RequestType::GetPrice => {
-                    let price = self.get_token_price(&token_address).unwrap_or(0);
-                    // build response
-                    let response = active_request.loan_uninit()?.write_payload(PriceResponse {
-                        wei_per_token: price,
-                    });
-                    response.send()?;
+        if let Some(active_request) = self.ipc_server.receive()? {
+            self.handle_request(active_request)?;
+        }
+        if let Some(active_request) = self.subscription_server.receive()? {
+            self.handle_request(active_request)?;
+        }
+
+        Ok(())
+    }
+
+    /// Answer a request received on either service
+    #[inline]
+    fn handle_request(&self, active_request: OpenRequest) -> Result<(), Error> {
+        // Extract token address
+        let request = *active_request.payload();
+        let token_address = Address::from(request.token_address);
+
+        match request.request_type {
+            RequestType::GetPrice => {
+                let snapshot = self.get_snapshot(&token_address);
+                // build response
+                let response = active_request
+                    .loan_uninit()?
+                    .write_payload(PriceResponse::from(&snapshot));
+                response.send()?;
+            }
+            RequestType::GetPair => {
+                let snapshot = self.get_pair_snapshot(&token_address);
+                active_request.send_copy(PriceResponse::from(&snapshot))?;
+            }
+            RequestType::RemoveToken => {
+                self.remove_token(&token_address);
+                // For remove operations, return the removed status without a price
+                let response = active_request.loan_uninit()?.write_payload(PriceResponse {
+                    token_address: request.token_address,
+                    status: TokenStatus::Removed,
+                    ..Default::default()
+                });
+                response.send()?;
+                info!("Token {:?} removed via IPC request", token_address);
+            }
+            RequestType::AddToken => {
+                // Answered once the lookup completes, see `apply_discovery`
+                if let Ok(mut pending_adds) = self.pending_adds.lock() {
+                    pending_adds.push((token_address, active_request));
+                }
+                self.lookup(Lookup::AddToken(token_address));
+            }
+            RequestType::ListTokens => {
+                let tokens = self.list_tokens(request.offset as usize);
+                self.send_snapshots(&active_request, request.request_type, tokens);
+            }
+            RequestType::GetBatchPrices => {
+                let count = (request.token_count as usize).min(MAX_BATCH_SIZE);
+                let tokens = request.token_addresses[..count]
+                    .iter()
+                    .map(|token| Address::from(*token));
+                self.send_snapshots(&active_request, request.request_type, tokens);
+            }
+            RequestType::Subscribe => {
+                // The current snapshot first, then one response per price change
+                let snapshot = self.get_snapshot(&token_address);
+                active_request.send_copy(PriceResponse::from(&snapshot))?;
+                if let Ok(mut subscriptions) = self.subscriptions.lock() {
+                    subscriptions.push((token_address, active_request));
+                    self.subscribed_tokens.insert(token_address);
                 }
-                RequestType::RemoveToken => {
-                    self.remove_token(&token_address);
-                    // For remove operations, return 0 as price (not applicable)
-                    let response = active_request.loan_uninit()?.write_payload(PriceResponse {
-                        wei_per_token: 0,
+            }
+            RequestType::Unsubscribe => {
+                let origin = active_request.origin();
+                if let Ok(mut subscriptions) = self.subscriptions.lock() {
+                    // Dropping the request closes the subscriber's stream
+                    subscriptions.retain(|(token, subscription)| {
+                        *token != token_address || subscription.origin() != origin
                     });
-                    response.send()?;
-                    info!("Token {:?} removed via IPC request", token_address);
+                    self.forget_unsubscribed(&subscriptions, &token_address);
                 }
+                let snapshot = self.get_snapshot(&token_address);
+                active_request.send_copy(PriceResponse::from(&snapshot))?;
             }
         }
 
         Ok(())
     }
 
+    /// Answer a request with one snapshot per token
+    ///
+    /// A failed send abandons this request only, the client receives the snapshots sent
+    /// before it.
+    #[inline]
+    fn send_snapshots(
+        &self,
+        active_request: &OpenRequest,
+        request_type: RequestType,
+        tokens: impl IntoIterator<Item = Address>,
+    ) {
+        for token in tokens {
+            let snapshot = self.get_snapshot(&token);
+            if let Err(e) = active_request.send_copy(PriceResponse::from(&snapshot)) {
+                warn!(
+                    ?e,
+                    "Failed to answer {:?} request with token {:?}", request_type, token
+                );
+                break;
+            }
+        }
+    }
+
+    /// Publish the new price of `token` and answer its open subscriptions
+    ///
+    /// `quote_price` and `update` are the values just stored for the token.
+    #[inline]
+    fn price_changed(&self, token: Address, quote_price: U256, update: &PriceUpdate) {
+        let rate = self
+            .get_quote_rate(&self.get_token_quote(&token))
+            .unwrap_or_default();
+        let tick = PriceTick {
+            token_address: token.0.0,
+            source: update.source,
+            wei_per_token: mul_scaled(quote_price, rate.wei_per_quote).to_be_bytes(),
+            usd_per_token: mul_scaled(quote_price, rate.usd_per_quote).to_be_bytes(),
+            block_number: update.meta.block_number,
+            timestamp: update.meta.timestamp,
+        };
+        if let Err(e) = self.publisher.send_copy(tick) {
+            warn!(?e, "Failed to publish price update of token {:?}", token);
+        } else if let Err(e) = self.notifier.notify() {
+            warn!(?e, "Failed to notify price update of token {:?}", token);
+        }
+        self.notify_subscribers(token);
+    }
+
+    /// Send the snapshot of a token to its subscribers, dropping the disconnected ones
+    ///
+    /// Returns right away when the token has no subscriber.
+    #[inline]
+    fn notify_subscribers(&self, token: Address) {
+        if !self.subscribed_tokens.contains(&token) {
+            return;
+        }
+        let snapshot = self.get_snapshot(&token);
+        let Ok(mut subscriptions) = self.subscriptions.lock() else {
+            return;
+        };
+
+        let response = PriceResponse::from(&snapshot);
+        subscriptions.retain(|(subscribed, subscription)| {
+            if *subscribed != snapshot.token {
+                return true;
+            }
+            if !subscription.is_connected() {
+                return false;
+            }
+            // A full buffer overwrites the oldest update, so sending only fails for good
+            match subscription.send_copy(response) {
+                Ok(_) => true,
+                Err(e) => {
+                    warn!(?e, "Dropped a subscription to token {:?}", snapshot.token);
+                    false
+                }
+            }
+        });
+        self.forget_unsubscribed(&subscriptions, &token);
+    }
+
+    /// Stop notifying `token` once its last subscription is gone
     #[inline]
-    fn handle_fourmeme_event(&self, event: FourmemeEvent) {
+    fn forget_unsubscribed(&self, subscriptions: &[(Address, OpenRequest)], token: &Address) {
+        if !subscriptions
+            .iter()
+            .any(|(subscribed, _)| subscribed == token)
+        {
+            self.subscribed_tokens.remove(token);
+        }
+    }
+
+    #[inline]
+    fn handle_fourmeme_event(&self, event: FourmemeEvent, meta: EventMeta) {
         match event {
             FourmemeEvent::TokenPurchase(purchase) => {
-                // Purchase event: update token price
+                // Purchase event: update token price and curve state
                 let token = purchase.token;
-                let price = purchase.price.to::<u128>();
-                self.update_token_price(token, price);
+                self.ensure_token_info(token);
+                self.update_curve_price(token, purchase.price, CurveState::from(&purchase), meta);
             }
             FourmemeEvent::TokenSale(sale) => {
-                // Sale event: update token price
+                // Sale event: update token price and curve state
                 let token = sale.token;
-                let price = sale.price.to::<u128>();
-                self.update_token_price(token, price);
+                self.ensure_token_info(token);
+                self.update_curve_price(token, sale.price, CurveState::from(&sale), meta);
             }
             FourmemeEvent::TokenCreate(create) => {
                 let token = create.token;
-                self.update_token_price(token, 0);
+                self.supplies.insert(token, create.totalSupply);
+                // Tracked from now on, but only priced and published once its curve is read
+                // or traded
+                self.update_token_price(token, U256::ZERO);
+                // Nothing is sold yet, so its first trades cross thresholds from zero. The
+                // size of its curve is unknown until it is read, but no curve offers more
+                // than the supply
+                let state = CurveState {
+                    offers: create.totalSupply,
+                    funds: U256::ZERO,
+                };
+                self.curves.insert(token, state);
+                self.ensure_token_info(token);
             }
             FourmemeEvent::LiquidityAdded(liquidity) => {
-                let other_token = liquidity.quote;
-                if other_token != BNB_ADDRESS {
-                    self.remove_token(&liquidity.base);
-                }
+                self.curves.remove(&liquidity.base);
+                self.token_curves.remove(&liquidity.base);
+                self.emit_curve_event(CurveEvent::Graduated {
+                    token: liquidity.base,
+                    quote: liquidity.quote,
+                });
+
+                self.migrated.insert(liquidity.base);
+                self.token_quotes.insert(liquidity.base, liquidity.quote);
+                self.ensure_metadata(liquidity.base);
+                self.ensure_quote_tracked(liquidity.quote);
+                self.migrate_to_pancake(liquidity.base, liquidity.quote, meta);
                 info!("FourmemeLiquidityAdded: {:?}", liquidity);
             }
         }
     }
 
+    /// Store a curve trade price with its curve state
+    #[inline]
+    fn update_curve_price(&self, token: Address, price: U256, state: CurveState, meta: EventMeta) {
+        let price = self.curve_price(&token, price);
+        let update = PriceUpdate {
+            source: PriceSource::FourmemeCurve,
+            meta,
+            reserve_token: state.offers,
+            reserve_quote: state.funds,
+        };
+        self.update_token_price(token, price);
+        self.updates.insert(token, update);
+        if state != CurveState::default() {
+            self.update_curve_state(token, state);
+        }
+        self.price_changed(token, price, &update);
+    }
+
+    /// Store the latest curve state and emit the thresholds crossed since the previous one
+    #[inline]
+    fn update_curve_state(&self, token: Address, state: CurveState) {
+        let previous = self.curves.insert(token, state);
+        let progress = self.progress(token, state);
+
+        for threshold_bps in thresholds_crossed(
+            &self.curve_of(&token),
+            previous.as_ref(),
+            &state,
+            &self.progress_thresholds,
+        ) {
+            info!(
+                "Token {:?} curve progress crossed {} bps, {:?} of quote {:?} remaining",
+                token, threshold_bps, progress.funds_remaining, progress.quote
+            );
+            self.emit_curve_event(CurveEvent::ThresholdCrossed {
+                threshold_bps,
+                progress,
+            });
+        }
+    }
+
     #[inline]
-    fn handle_pancake_event(&self, event: PancakeSwapEvent, pair_address: Address) {
+    fn emit_curve_event(&self, event: CurveEvent) {
+        if let Some(curve_events) = &self.curve_events {
+            // The receiver may have been dropped, tracking goes on regardless
+            let _ = curve_events.send(event);
+        }
+    }
+
+    #[inline]
+    fn progress(&self, token: Address, state: CurveState) -> CurveProgress {
+        let curve = self.curve_of(&token);
+        CurveProgress {
+            token,
+            quote: self.get_token_quote(&token),
+            state,
+            progress_bps: curve.progress_bps(&state),
+            funds_remaining: curve.funds_to_graduate(&state),
+        }
+    }
+
+    /// Bonding curve of a token, the configured curve until its own is read
+    #[inline]
+    fn curve_of(&self, token: &Address) -> BondingCurve {
+        self.token_curves
+            .get(token)
+            .map_or(self.curve, |curve| *curve.value())
+    }
+
+    #[inline]
+    fn handle_pancake_event(
+        &self,
+        event: PancakeSwapEvent,
+        pair_address: Address,
+        meta: EventMeta,
+    ) {
         match event {
             PancakeSwapEvent::Sync(sync) => {
+                if let Some(quote_pair) = self.quote_pairs.get(&pair_address) {
+                    let (quote, is_token0) = *quote_pair.value();
+                    let reserve0 = U256::from(sync.reserve0);
+                    let reserve1 = U256::from(sync.reserve1);
+                    self.update_quote_rate(quote, is_token0, reserve0, reserve1);
+                    return;
+                }
+
                 let Some(pair_info) = self.pairs.get(&pair_address) else {
                     return; // Skip if pair not found
                 };
                 let (token, is_token0) = *pair_info.value();
 
-                let reserve0 = sync.reserve0.to::<u128>();
-                let reserve1 = sync.reserve1.to::<u128>();
-
-                // Calculate wei per token
-                // If token is token0, price = reserve1 / reserve0 (BNB per token)
-                // If token is token1, price = reserve0 / reserve1 (BNB per token)
-                let price = if is_token0 {
-                    // token is token0, WBNB is token1
-                    if reserve0 > 0 {
-                        (reserve1 * 1_000_000_000_000_000_000_u128) / reserve0
-                    } else {
-                        0
-                    }
-                } else {
-                    // token is token1, WBNB is token0
-                    if reserve1 > 0 {
-                        (reserve0 * 1_000_000_000_000_000_000_u128) / reserve1
-                    } else {
-                        0
-                    }
-                };
-
-                self.update_token_price(token, price);
-                info!("Token {:?} price updated to {:?}, reserve0: {:?}, reserve1: {:?}", token, price, reserve0, reserve1);
+                let reserve0 = U256::from(sync.reserve0);
+                let reserve1 = U256::from(sync.reserve1);
+                self.update_pair_price(token, is_token0, reserve0, reserve1, meta);
             }
             PancakeSwapEvent::PairCreated(pair_created) => {
-                if self.exist_token(&pair_created.token0) {
-                    // token0 is our tracked token, token1 is WBNB
-                    self.pairs
-                        .insert(pair_created.pair, (pair_created.token0, true));
+                if self.exist_token(&pair_created.token0)
+                    && self.pair_quote_token(&pair_created.token0) == pair_created.token1
+                {
+                    // token0 is our tracked token, token1 is its quote
+                    self.insert_pair(pair_created.pair, pair_created.token0, true);
                     return;
                 };
 
-                if self.exist_token(&pair_created.token1) {
-                    // token1 is our tracked token, token0 is WBNB
-                    self.pairs
-                        .insert(pair_created.pair, (pair_created.token1, false));
+                if self.exist_token(&pair_created.token1)
+                    && self.pair_quote_token(&pair_created.token1) == pair_created.token0
+                {
+                    // token1 is our tracked token, token0 is its quote
+                    self.insert_pair(pair_created.pair, pair_created.token1, false);
                     return;
                 };
             }
@@ -208,10 +638,324 @@ impl PriceTrack {
         }
     }
 
+    /// Register the Pancake pair a token is priced from, replacing its previous pair
+    #[inline]
+    fn insert_pair(&self, pair: Address, token: Address, is_token0: bool) {
+        self.pairs.insert(pair, (token, is_token0));
+        let previous = self
+            .token_pairs
+            .insert(token, pair)
+            .filter(|previous| *previous != pair);
+        if let Some(previous) = previous {
+            self.pairs.remove(&previous);
+        }
+    }
+
+    /// Link a token that left the bonding curve to its Pancake pair and seed its price
+    ///
+    /// The pair address is derived with CREATE2, so the `Sync` events of the migration are
+    /// picked up even when the `PairCreated` event was missed.
+    #[inline]
+    fn migrate_to_pancake(&self, token: Address, quote: Address, meta: EventMeta) {
+        let quote_token = if quote == BNB_ADDRESS { WBNB } else { quote };
+        let pair = pair_address(token, quote_token);
+        self.insert_pair(pair, token, token < quote_token);
+
+        // The next Sync event of the pair updates the price if the read fails
+        self.lookup(Lookup::PairReserves(pair, meta));
+        info!("Token {:?} migrated to pair {:?}", token, pair);
+    }
+
+    /// Look up the metadata and the quote asset of a token the first time it is seen
+    ///
+    /// The token is priced as BNB until its quote is known.
+    #[inline]
+    fn ensure_token_info(&self, token: Address) {
+        self.ensure_metadata(token);
+        if !self.token_quotes.contains_key(&token) {
+            self.lookup(Lookup::TokenInfo(token));
+        }
+    }
+
+    /// Start tracking the BNB rate of a quote asset
+    #[inline]
+    fn ensure_quote_tracked(&self, quote: Address) {
+        if quote == BNB_ADDRESS || self.quote_rates.contains_key(&quote) {
+            return;
+        }
+
+        let pair = pair_address(quote, WBNB);
+        self.quote_pairs.insert(pair, (quote, quote < WBNB));
+        self.lookup(Lookup::QuoteRate(quote));
+    }
+
+    /// Read the current rate of every tracked quote asset
+    #[inline]
+    fn seed_quote_rates(&self) {
+        let quotes: Vec<_> = self
+            .quote_pairs
+            .iter()
+            .map(|entry| entry.value().0)
+            .collect();
+
+        for quote in quotes {
+            self.lookup(Lookup::QuoteRate(quote));
+        }
+    }
+
+    /// Run an RPC lookup off the event loop, unless it is in flight or backing off
+    ///
+    /// The result comes back through `lookup_rx` and is applied by `apply_lookup`.
+    #[inline]
+    fn lookup(&self, lookup: Lookup) {
+        let backing_off = self
+            .failed_lookups
+            .get(&lookup)
+            .is_some_and(|backoff| !backoff.ready(Instant::now()));
+        if backing_off || !self.lookups_in_flight.insert(lookup) {
+            return;
+        }
+
+        let client = self.rpc.client.clone();
+        let lookup_tx = self.lookup_tx.clone();
+        tokio::spawn(async move {
+            let found = lookup.run(&client).await;
+            // The receiver is only dropped when the tracker shuts down
+            let _ = lookup_tx.send((lookup, found));
+        });
+    }
+
+    /// Apply the result of a lookup, or back off the lookup when it failed
+    #[inline]
+    fn apply_lookup(&self, lookup: Lookup, found: Result<Found, Error>) {
+        self.lookups_in_flight.remove(&lookup);
+        let found = match found {
+            Ok(found) => {
+                self.failed_lookups.remove(&lookup);
+                found
+            }
+            Err(e) => {
+                let delay = self
+                    .failed_lookups
+                    .entry(lookup)
+                    .or_default()
+                    .fail(Instant::now());
+                warn!(
+                    ?e,
+                    "Lookup {:?} failed, next attempt in {:?}", lookup, delay
+                );
+                return;
+            }
+        };
+
+        match found {
+            Found::TokenInfo(token, info) => self.apply_curve_info(token, info),
+            Found::Metadata(token, metadata) => {
+                self.metadata.insert(token, metadata);
+                self.reprice_pair(token);
+            }
+            Found::QuoteRate {
+                quote,
+                metadata,
+                reserves: (reserve0, reserve1),
+            } => {
+                self.metadata.insert(quote, metadata);
+                self.update_quote_rate(quote, quote < WBNB, reserve0, reserve1);
+            }
+            Found::PairReserves {
+                pair,
+                meta,
+                reserves: (reserve0, reserve1),
+            } => {
+                let Some((token, is_token0)) = self.pairs.get(&pair).map(|entry| *entry.value())
+                else {
+                    return; // Not a tracked pair
+                };
+                self.update_pair_price(token, is_token0, reserve0, reserve1, meta);
+            }
+            Found::AddToken(discovery) => self.apply_discovery(discovery),
+            Found::Chainlink(round) => self.apply_chainlink(round),
+        }
+    }
+
+    /// Record the quote and supply of a token read from the token manager
+    #[inline]
+    fn apply_curve_info(&self, token: Address, info: CurveInfo) {
+        self.token_quotes.insert(token, info.quote);
+        self.supplies.insert(token, info.total_supply);
+        self.token_curves.insert(token, info.curve);
+        self.ensure_quote_tracked(info.quote);
+
+        // Trades seen before the lookup completed were priced as BNB, and a token that was
+        // only created has no price yet
+        let unpriced = self
+            .get_quote_price(&token)
+            .is_some_and(|price| price.is_zero());
+        if (info.quote != BNB_ADDRESS || unpriced)
+            && !info.last_price.is_zero()
+            && self.exist_token(&token)
+            && !self.migrated.contains(&token)
+        {
+            let meta = self
+                .updates
+                .get(&token)
+                .map(|update| update.meta)
+                .unwrap_or_default();
+            let state = CurveState {
+                offers: info.offers,
+                funds: info.funds,
+            };
+            self.update_curve_price(token, info.last_price, state, meta);
+        }
+    }
+
+    /// Update the BNB rate of a quote asset from the reserves of its WBNB pair
+    #[inline]
+    fn update_quote_rate(&self, quote: Address, is_token0: bool, reserve0: U256, reserve1: U256) {
+        let (reserve_quote, reserve_bnb) = if is_token0 {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        };
+        if reserve_quote.is_zero() {
+            return;
+        }
+
+        let wei_per_quote = price_from_reserves(
+            reserve_quote,
+            self.decimals_of(&quote),
+            reserve_bnb,
+            DEFAULT_DECIMALS,
+        );
+        self.quote_rates.insert(quote, wei_per_quote);
+    }
+
+    /// Look up the ERC-20 metadata of a token or quote asset the first time it is seen
+    #[inline]
+    fn ensure_metadata(&self, token: Address) {
+        if token != BNB_ADDRESS && !self.metadata.contains_key(&token) {
+            self.lookup(Lookup::Metadata(token));
+        }
+    }
+
+    /// Decimals of a token or quote asset, 18 until its metadata is read
+    #[inline]
+    fn decimals_of(&self, token: &Address) -> u8 {
+        if *token == BNB_ADDRESS || *token == WBNB {
+            return DEFAULT_DECIMALS;
+        }
+        self.metadata
+            .get(token)
+            .map_or(DEFAULT_DECIMALS, |metadata| metadata.decimals)
+    }
+
+    /// Normalize a curve trade price, in quote units per whole token, to the 1e18 scale
+    #[inline]
+    fn curve_price(&self, token: &Address, price: U256) -> U256 {
+        let quote = self.get_token_quote(token);
+        div_scaled(price, pow10(self.decimals_of(&quote)))
+    }
+
+    /// Read the BNB/USD answer of the Chainlink aggregator off the event loop
+    #[inline]
+    fn refresh_chainlink(&self) {
+        if let Some(aggregator) = self.chainlink_feed {
+            self.lookup(Lookup::Chainlink(aggregator));
+        }
+    }
+
+    /// Store a Chainlink round unless it is stale, in which case the pair rate takes over
+    #[inline]
+    fn apply_chainlink(&self, round: ChainlinkRound) {
+        match round.usd_per_bnb(self.chainlink_heartbeat, snapshot::now()) {
+            Ok(usd_per_bnb) => {
+                if let Ok(mut rate) = self.chainlink_usd_per_bnb.write() {
+                    *rate = Some((usd_per_bnb, round.updated_at.saturating_to()));
+                }
+            }
+            Err(e) => warn!(?e, "Rejected Chainlink round {:?}", round),
+        }
+    }
+
+    /// Token the pair of `token` is expected to be quoted in
+    #[inline]
+    fn pair_quote_token(&self, token: &Address) -> Address {
+        let quote = self.get_token_quote(token);
+        if quote == BNB_ADDRESS { WBNB } else { quote }
+    }
+
+    /// Update the price of a token from the reserves of its Pancake pair
+    #[inline]
+    fn update_pair_price(
+        &self,
+        token: Address,
+        is_token0: bool,
+        reserve0: U256,
+        reserve1: U256,
+        meta: EventMeta,
+    ) {
+        let (reserve_token, reserve_quote) = if is_token0 {
+            // token is token0, the quote is token1
+            (reserve0, reserve1)
+        } else {
+            // token is token1, the quote is token0
+            (reserve1, reserve0)
+        };
+        self.set_pair_price(token, reserve_token, reserve_quote, meta);
+    }
+
+    /// Recompute the pair price of a token from its stored reserves, e.g. once its decimals
+    /// are known
+    #[inline]
+    fn reprice_pair(&self, token: Address) {
+        let Some(update) = self.updates.get(&token).map(|update| *update.value()) else {
+            return;
+        };
+        if update.source == PriceSource::PancakePair && self.exist_token(&token) {
+            self.set_pair_price(
+                token,
+                update.reserve_token,
+                update.reserve_quote,
+                update.meta,
+            );
+        }
+    }
+
+    /// Store the price of a token given by the reserves of its Pancake pair
+    #[inline]
+    fn set_pair_price(
+        &self,
+        token: Address,
+        reserve_token: U256,
+        reserve_quote: U256,
+        meta: EventMeta,
+    ) {
+        // Quote per whole token
+        let token_decimals = self.decimals_of(&token);
+        let quote_decimals = self.decimals_of(&self.pair_quote_token(&token));
+        let price =
+            price_from_reserves(reserve_token, token_decimals, reserve_quote, quote_decimals);
+
+        let update = PriceUpdate {
+            source: PriceSource::PancakePair,
+            meta,
+            reserve_token,
+            reserve_quote,
+        };
+        self.update_token_price(token, price);
+        self.updates.insert(token, update);
+        self.price_changed(token, price, &update);
+        info!(
+            "Token {:?} price updated to {:?}, reserve token: {:?}, reserve quote: {:?}",
+            token, price, reserve_token, reserve_quote
+        );
+    }
+
     /// Update token price
     #[inline]
-    pub fn update_token_price(&self, token: Address, price: u128) {
+    pub fn update_token_price(&self, token: Address, price: U256) {
         self.tokens.insert(token, price);
+        self.removed.remove(&token);
     }
 
     /// Check if token exists    
@@ -224,10 +968,455 @@ impl PriceTrack {
     #[inline]
     pub fn remove_token(&self, token: &Address) {
         self.tokens.remove(token);
+        self.curves.remove(token);
+        self.token_curves.remove(token);
+        self.token_quotes.remove(token);
+        self.supplies.remove(token);
+        self.metadata.remove(token);
+        self.updates.remove(token);
+        self.migrated.remove(token);
+        // Sync events of the pair no longer reprice the token
+        if let Some((_, pair)) = self.token_pairs.remove(token) {
+            self.pairs.remove(&pair);
+        }
+        self.removed.insert(*token);
+        self.notify_subscribers(*token);
+    }
+
+    /// Start tracking a token discovered elsewhere
+    ///
+    /// The token is priced from its Pancake pair when the pair already holds liquidity,
+    /// otherwise from its Fourmeme bonding curve. Unknown tokens are left untracked.
+    /// `AddToken` IPC requests run the same lookup off the event loop.
+    #[inline]
+    pub async fn add_token(&self, token: Address) {
+        self.apply_discovery(lookup::discover(&self.rpc.client, token).await);
+    }
+
+    /// Track a token from what its lookup found and answer its pending `AddToken` requests
+    #[inline]
+    fn apply_discovery(&self, discovery: Discovery) {
+        let Discovery {
+            token,
+            meta,
+            metadata,
+            info,
+            pair,
+        } = discovery;
+
+        if let Some(metadata) = metadata {
+            self.metadata.insert(token, metadata);
+        }
+        if let Some(info) = &info {
+            self.token_quotes.insert(token, info.quote);
+            self.supplies.insert(token, info.total_supply);
+            self.token_curves.insert(token, info.curve);
+            self.ensure_quote_tracked(info.quote);
+        }
+
+        if let Some(pair) = pair {
+            self.insert_pair(pair.pair, token, pair.is_token0);
+            self.migrated.insert(token);
+            self.update_pair_price(token, pair.is_token0, pair.reserve0, pair.reserve1, meta);
+        } else if let Some(info) = info {
+            let state = CurveState {
+                offers: info.offers,
+                funds: info.funds,
+            };
+            self.update_curve_price(token, info.last_price, state, meta);
+        }
+
+        let snapshot = self.get_snapshot(&token);
+        let Ok(mut pending_adds) = self.pending_adds.lock() else {
+            return;
+        };
+        pending_adds.retain(|(added, request)| {
+            if *added != token {
+                return true;
+            }
+            if let Err(e) = request.send_copy(PriceResponse::from(&snapshot)) {
+                warn!(?e, "Failed to answer AddToken request of token {:?}", token);
+            }
+            false
+        });
+        info!("Token {:?} added, status {:?}", token, snapshot.status);
     }
 
+    /// Tracked tokens sorted by address, at most `MAX_BATCH_SIZE` from `offset`
     #[inline]
-    pub fn get_token_price(&self, token: &Address) -> Option<u128> {
+    pub fn list_tokens(&self, offset: usize) -> Vec<Address> {
+        let mut tokens: Vec<Address> = self.tokens.iter().map(|entry| *entry.key()).collect();
+        tokens.sort_unstable();
+        tokens
+            .into_iter()
+            .skip(offset)
+            .take(MAX_BATCH_SIZE)
+            .collect()
+    }
+
+    /// Pancake pair a token is priced from
+    #[inline]
+    pub fn get_pair(&self, token: &Address) -> Option<Address> {
+        self.token_pairs.get(token).map(|pair| *pair.value())
+    }
+
+    /// Pancake pair of a token with its latest reserves, pair fields zero while on the curve
+    #[inline]
+    pub fn get_pair_snapshot(&self, token: &Address) -> PairSnapshot {
+        let status = self.get_status(token);
+        let Some(pair) = self.get_pair(token) else {
+            return PairSnapshot {
+                token: *token,
+                status,
+                ..Default::default()
+            };
+        };
+
+        let is_token0 = self.pairs.get(&pair).is_some_and(|entry| entry.value().1);
+        let update = self
+            .updates
+            .get(token)
+            .map(|update| *update.value())
+            .filter(|update| update.source == PriceSource::PancakePair)
+            .unwrap_or_default();
+
+        PairSnapshot {
+            token: *token,
+            status,
+            pair,
+            quote: self.pair_quote_token(token),
+            is_token0,
+            update,
+        }
+    }
+
+    /// Tracking status of a token
+    #[inline]
+    pub fn get_status(&self, token: &Address) -> TokenStatus {
+        if self.exist_token(token) {
+            if self.migrated.contains(token) {
+                TokenStatus::Migrated
+            } else {
+                TokenStatus::Tracked
+            }
+        } else if self.removed.contains(token) {
+            TokenStatus::Removed
+        } else {
+            TokenStatus::Unknown
+        }
+    }
+
+    /// Price, venue, reserves and curve progress of a token in one read
+    #[inline]
+    pub fn get_snapshot(&self, token: &Address) -> PriceSnapshot {
+        let status = self.get_status(token);
+        if !matches!(status, TokenStatus::Tracked | TokenStatus::Migrated) {
+            return PriceSnapshot {
+                token: *token,
+                status,
+                ..Default::default()
+            };
+        }
+
+        let progress_bps = match status {
+            TokenStatus::Migrated => 10_000,
+            _ => self
+                .get_curve_progress(token)
+                .map_or(0, |progress| progress.progress_bps),
+        };
+
+        PriceSnapshot {
+            token: *token,
+            status,
+            quote: self.get_token_quote(token),
+            pair: self.get_pair(token).unwrap_or_default(),
+            wei_per_token: self.get_token_price(token).unwrap_or_default(),
+            usd_per_token: self.get_token_price_usd(token).unwrap_or_default(),
+            usd_market_cap: self.get_market_cap_usd(token).unwrap_or_default(),
+            update: self
+                .updates
+                .get(token)
+                .map(|update| *update.value())
+                .unwrap_or_default(),
+            progress_bps,
+        }
+    }
+
+    /// Price of a token in BNB wei per token, `None` if the token or its quote rate is unknown
+    #[inline]
+    pub fn get_token_price(&self, token: &Address) -> Option<U256> {
+        let price = self.get_quote_price(token)?;
+        let rate = self.get_quote_rate(&self.get_token_quote(token))?;
+        Some(mul_scaled(price, rate.wei_per_quote))
+    }
+
+    /// Price of a token in USD per token scaled by 1e18
+    #[inline]
+    pub fn get_token_price_usd(&self, token: &Address) -> Option<U256> {
+        let price = self.get_quote_price(token)?;
+        let rate = self.get_quote_rate(&self.get_token_quote(token))?;
+        if rate.usd_per_quote.is_zero() {
+            return None;
+        }
+        Some(mul_scaled(price, rate.usd_per_quote))
+    }
+
+    /// USD market cap of a token scaled by 1e18
+    #[inline]
+    pub fn get_market_cap_usd(&self, token: &Address) -> Option<U256> {
+        let supply = *self.supplies.get(token)?.value();
+        let usd_price = self.get_token_price_usd(token)?;
+        Some(mul_div(supply, usd_price, pow10(self.decimals_of(token))))
+    }
+
+    /// ERC-20 metadata of a tracked token or quote asset
+    #[inline]
+    pub fn get_token_metadata(&self, token: &Address) -> Option<TokenMetadata> {
+        self.metadata
+            .get(token)
+            .map(|metadata| metadata.value().clone())
+    }
+
+    /// Price of a token in whole quote tokens per whole token, scaled by 1e18
+    #[inline]
+    pub fn get_quote_price(&self, token: &Address) -> Option<U256> {
         self.tokens.get(token).map(|price| *price.value())
     }
+
+    /// Quote asset of a token, `BNB_ADDRESS` for BNB
+    #[inline]
+    pub fn get_token_quote(&self, token: &Address) -> Address {
+        self.token_quotes
+            .get(token)
+            .map_or(BNB_ADDRESS, |quote| *quote.value())
+    }
+
+    /// Rates of a quote asset, `None` until its WBNB pair has been read
+    ///
+    /// The USD rate is zero while the BNB/USD rate is unknown.
+    #[inline]
+    pub fn get_quote_rate(&self, quote: &Address) -> Option<QuoteRate> {
+        let wei_per_quote = if *quote == BNB_ADDRESS {
+            PRICE_SCALE
+        } else {
+            *self.quote_rates.get(quote)?.value()
+        };
+        let usd_per_quote = if is_stable(quote) {
+            PRICE_SCALE
+        } else {
+            mul_scaled(wei_per_quote, self.usd_per_bnb())
+        };
+
+        Some(QuoteRate {
+            wei_per_quote,
+            usd_per_quote,
+        })
+    }
+
+    /// USD per BNB scaled by 1e18, zero while unknown
+    ///
+    /// The Chainlink answer is used when a feed is configured and the answer is within the
+    /// heartbeat, the WBNB pair of the reference stable quote (USDT by default) otherwise.
+    #[inline]
+    pub fn usd_per_bnb(&self) -> U256 {
+        let heartbeat = self.chainlink_heartbeat.as_secs();
+        let chainlink = self
+            .chainlink_usd_per_bnb
+            .read()
+            .ok()
+            .and_then(|rate| *rate)
+            .filter(|(_, updated_at)| snapshot::now().saturating_sub(*updated_at) <= heartbeat);
+        if let Some((usd_per_bnb, _)) = chainlink {
+            return usd_per_bnb;
+        }
+
+        self.quote_rates
+            .get(&self.bnb_usd_quote)
+            .map_or(U256::ZERO, |rate| div_scaled(PRICE_SCALE, *rate.value()))
+    }
+
+    /// Latest bonding curve state of a token still trading on the curve
+    #[inline]
+    pub fn get_curve_state(&self, token: &Address) -> Option<CurveState> {
+        self.curves.get(token).map(|state| *state.value())
+    }
+
+    /// Bonding curve progress of a token still trading on the curve
+    #[inline]
+    pub fn get_curve_progress(&self, token: &Address) -> Option<CurveProgress> {
+        self.get_curve_state(token)
+            .map(|state| self.progress(*token, state))
+    }
+}
+
+/// Rpc over a mocked provider with no response queued, so every lookup fails
+#[cfg(test)]
+fn test_rpc() -> Rpc {
+    use alloy::providers::{Provider, ProviderBuilder, mock::Asserter};
+    use rpc::{nonce::NonceManager, wallet::WalletPool};
+    use std::sync::Arc;
+
+    let client = ProviderBuilder::new()
+        .connect_mocked_client(Asserter::new())
+        .erased();
+    let signers = WalletPool::parse_keys(&[
+        "0x0123456789012345678901234567890123456789012345678901234567890123",
+    ])
+    .unwrap();
+    let sender_address = signers[0].address();
+    Rpc {
+        client,
+        sender_address,
+        chain_id: 56,
+        gas_price: Arc::new(tokio::sync::Mutex::new(0)),
+        nonce: Arc::new(NonceManager::new(sender_address, 0)),
+        wallets: Arc::new(WalletPool::new(signers, 56).unwrap()),
+    }
+}
+
+#[tokio::test]
+async fn test_liquidity_added_registers_pair() {
+    use fourmeme::parser::LiquidityAdded;
+
+    // Lookups fail, so only the event itself is applied
+    let rpc = test_rpc();
+    // Services of its own, so a tracker running on this machine is left alone
+    let services =
+        ServiceNames::with_prefix(&format!("test_liquidity_added_{}", std::process::id()));
+    let tracker = PriceTrack::init_with_services(rpc, &services)
+        .await
+        .unwrap();
+
+    let token = Address::repeat_byte(0x11);
+    let event = FourmemeEvent::LiquidityAdded(LiquidityAdded {
+        base: token,
+        offers: U256::ZERO,
+        quote: BNB_ADDRESS,
+        funds: U256::from(24u64),
+    });
+    tracker.handle_fourmeme_event(event, EventMeta::at_block(1));
+
+    let pair = pair_address(token, WBNB);
+    assert_eq!(tracker.get_pair(&token), Some(pair));
+    assert_eq!(
+        tracker.pairs.get(&pair).map(|entry| *entry.value()),
+        Some((token, token < WBNB))
+    );
+    let snapshot = tracker.get_pair_snapshot(&token);
+    assert_eq!((snapshot.pair, snapshot.quote), (pair, WBNB));
+    assert_eq!(snapshot.is_token0, token < WBNB);
+
+    tracker.remove_token(&token);
+    assert_eq!(tracker.get_pair(&token), None);
+    assert!(!tracker.pairs.contains_key(&pair));
+}
+
+#[tokio::test]
+async fn test_token_create_waits_for_a_price() {
+    use fourmeme::parser::TokenCreate;
+
+    let services = ServiceNames::with_prefix(&format!("test_token_create_{}", std::process::id()));
+    let tracker = PriceTrack::init_with_services(test_rpc(), &services)
+        .await
+        .unwrap();
+    let node = NodeBuilder::new().create::<ipc::Service>().unwrap();
+    let ticks = node
+        .service_builder(&services.updates.as_str().try_into().unwrap())
+        .publish_subscribe::<PriceTick>()
+        .subscriber_max_buffer_size(UPDATE_BUFFER_SIZE)
+        .open_or_create()
+        .unwrap()
+        .subscriber_builder()
+        .buffer_size(UPDATE_BUFFER_SIZE)
+        .create()
+        .unwrap();
+
+    let token = Address::repeat_byte(0x44);
+    let event = FourmemeEvent::TokenCreate(TokenCreate {
+        creator: Address::repeat_byte(0x55),
+        token,
+        requestId: U256::from(1u64),
+        name: "Test".to_string(),
+        symbol: "TEST".to_string(),
+        totalSupply: U256::from(1_000_000_000u64) * PRICE_SCALE,
+        launchTime: U256::ZERO,
+        launchFee: U256::ZERO,
+    });
+    tracker.handle_fourmeme_event(event, EventMeta::at_block(1));
+
+    // Tracked, but nothing is published before the price is known
+    assert_eq!(tracker.get_status(&token), TokenStatus::Tracked);
+    assert!(ticks.receive().unwrap().is_none());
+
+    let info = CurveInfo {
+        quote: BNB_ADDRESS,
+        total_supply: U256::from(1_000_000_000u64) * PRICE_SCALE,
+        curve: BondingCurve::default(),
+        offers: U256::from(800_000_000u64) * PRICE_SCALE,
+        funds: U256::ZERO,
+        last_price: U256::from(6_000_000_000u64),
+    };
+    tracker.apply_lookup(Lookup::TokenInfo(token), Ok(Found::TokenInfo(token, info)));
+
+    let tick = *ticks.receive().unwrap().unwrap().payload();
+    assert_eq!(Address::from(tick.token_address), token);
+    assert_eq!(
+        U256::from_be_bytes(tick.wei_per_token),
+        U256::from(6_000_000_000u64)
+    );
+}
+
+#[tokio::test]
+async fn test_curve_progress_uses_token_curve() {
+    use fourmeme::parser::TokenCreate;
+
+    let services = ServiceNames::with_prefix(&format!("test_token_curve_{}", std::process::id()));
+    let mut tracker = PriceTrack::init_with_services(test_rpc(), &services)
+        .await
+        .unwrap();
+    let mut curve_events = tracker.subscribe_curve_events();
+
+    let token = Address::repeat_byte(0x66);
+    let supply = U256::from(1_000_000_000u64) * PRICE_SCALE;
+    let event = FourmemeEvent::TokenCreate(TokenCreate {
+        creator: Address::repeat_byte(0x55),
+        token,
+        requestId: U256::from(1u64),
+        name: "Test".to_string(),
+        symbol: "TEST".to_string(),
+        totalSupply: supply,
+        launchTime: U256::ZERO,
+        launchFee: U256::ZERO,
+    });
+    tracker.handle_fourmeme_event(event, EventMeta::at_block(1));
+
+    // A USD1 curve offering 700M tokens for 12,000 USD1, half of them sold
+    let usd1 = Address::repeat_byte(0x77);
+    let curve = BondingCurve {
+        max_offers: U256::from(700_000_000u64) * PRICE_SCALE,
+        max_funds: U256::from(12_000u64) * PRICE_SCALE,
+        ..Default::default()
+    };
+    let info = CurveInfo {
+        quote: usd1,
+        total_supply: supply,
+        curve,
+        offers: U256::from(350_000_000u64) * PRICE_SCALE,
+        funds: U256::from(4_000u64) * PRICE_SCALE,
+        last_price: U256::from(10_000_000_000_000u64),
+    };
+    tracker.apply_lookup(Lookup::TokenInfo(token), Ok(Found::TokenInfo(token, info)));
+
+    let progress = tracker.get_curve_progress(&token).unwrap();
+    assert_eq!(progress.quote, usd1);
+    assert_eq!(progress.progress_bps, 5_000);
+    assert_eq!(progress.funds_remaining, U256::from(8_000u64) * PRICE_SCALE);
+    assert_eq!(
+        curve_events.try_recv().unwrap(),
+        CurveEvent::ThresholdCrossed {
+            threshold_bps: 5_000,
+            progress
+        }
+    );
+    assert!(curve_events.try_recv().is_err());
 }
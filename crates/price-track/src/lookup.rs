@@ -0,0 +1,350 @@
+use abi::{AggregatorV3Interface, FourMemeContract, IERC20};
+use alloy::{
+    primitives::{Address, I256, U256, aliases::U80},
+    providers::{DynProvider, Provider},
+};
+use anyhow::Error;
+use fourmeme::{constants::FOURMEME_CONTRACT, quote::BondingCurve};
+use pancake_v2::{WBNB, pair::pair_address, parser::PancakeSwapPair};
+use std::time::{Duration, Instant};
+
+use crate::{
+    metadata::TokenMetadata,
+    quotes::{BNB_ADDRESS, div_scaled, pow10},
+    snapshot::EventMeta,
+};
+
+/// Delay before a failed lookup is retried, doubled after every further failure
+pub const LOOKUP_BACKOFF: Duration = Duration::from_secs(5);
+/// Longest delay between two attempts of a failing lookup
+pub const MAX_LOOKUP_BACKOFF: Duration = Duration::from_secs(600);
+
+/// RPC read run off the event loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lookup {
+    /// Quote asset, supply and curve state of a Fourmeme token
+    TokenInfo(Address),
+    /// ERC-20 metadata of a token
+    Metadata(Address),
+    /// Metadata and WBNB pair reserves of a quote asset
+    QuoteRate(Address),
+    /// Reserves of the Pancake pair a token migrated to, at the migration event
+    PairReserves(Address, EventMeta),
+    /// Everything needed to start tracking a token discovered elsewhere
+    AddToken(Address),
+    /// Latest round of a Chainlink aggregator
+    Chainlink(Address),
+}
+
+/// Lookup with its outcome, sent back to the event loop
+pub type LookupResult = (Lookup, Result<Found, Error>);
+
+/// Result of a lookup, applied back on the event loop
+#[derive(Debug, Clone)]
+pub enum Found {
+    TokenInfo(Address, CurveInfo),
+    Metadata(Address, TokenMetadata),
+    QuoteRate {
+        quote: Address,
+        metadata: TokenMetadata,
+        reserves: (U256, U256),
+    },
+    PairReserves {
+        pair: Address,
+        meta: EventMeta,
+        reserves: (U256, U256),
+    },
+    AddToken(Discovery),
+    Chainlink(ChainlinkRound),
+}
+
+/// Token manager view of a Fourmeme token
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CurveInfo {
+    pub quote: Address,
+    pub total_supply: U256,
+    /// Curve of the token, its `max_offers` is zero for tokens unknown to the token manager
+    pub curve: BondingCurve,
+    pub offers: U256,
+    pub funds: U256,
+    /// Price of the latest trade, in quote units per whole token
+    pub last_price: U256,
+}
+
+/// Reserves of the Pancake pair of a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PairReserves {
+    pub pair: Address,
+    pub is_token0: bool,
+    pub reserve0: U256,
+    pub reserve1: U256,
+}
+
+/// What is known about a token added through `AddToken`
+#[derive(Debug, Clone)]
+pub struct Discovery {
+    pub token: Address,
+    /// Block the lookup ran at
+    pub meta: EventMeta,
+    pub metadata: Option<TokenMetadata>,
+    /// `None` for tokens unknown to the token manager
+    pub info: Option<CurveInfo>,
+    /// `None` unless the pair against the quote holds liquidity
+    pub pair: Option<PairReserves>,
+}
+
+/// Latest round of a Chainlink aggregator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainlinkRound {
+    pub decimals: u8,
+    pub round_id: U80,
+    pub answer: I256,
+    /// Unix time in seconds of the last update
+    pub updated_at: U256,
+    pub answered_in_round: U80,
+}
+
+impl ChainlinkRound {
+    /// USD per BNB scaled by 1e18
+    ///
+    /// Fails when the answer is not positive, was carried over from an earlier round or is
+    /// older than `heartbeat` at `now`, in unix seconds.
+    #[inline]
+    pub fn usd_per_bnb(&self, heartbeat: Duration, now: u64) -> Result<U256, Error> {
+        if !self.answer.is_positive() {
+            return Err(Error::msg(format!("non-positive answer {}", self.answer)));
+        }
+        if self.answered_in_round < self.round_id {
+            return Err(Error::msg(format!(
+                "round {} answered in round {}",
+                self.round_id, self.answered_in_round
+            )));
+        }
+        let age = now.saturating_sub(self.updated_at.saturating_to::<u64>());
+        if self.updated_at.is_zero() || age > heartbeat.as_secs() {
+            return Err(Error::msg(format!(
+                "answer updated {}s ago, heartbeat is {}s",
+                age,
+                heartbeat.as_secs()
+            )));
+        }
+
+        Ok(div_scaled(self.answer.unsigned_abs(), pow10(self.decimals)))
+    }
+}
+
+impl Lookup {
+    /// Run the lookup against `client`
+    #[inline]
+    pub async fn run(self, client: &DynProvider) -> Result<Found, Error> {
+        Ok(match self {
+            Lookup::TokenInfo(token) => {
+                Found::TokenInfo(token, read_curve_info(client, token).await?)
+            }
+            Lookup::Metadata(token) => Found::Metadata(token, read_metadata(client, token).await?),
+            Lookup::QuoteRate(quote) => Found::QuoteRate {
+                quote,
+                metadata: read_metadata(client, quote).await?,
+                reserves: read_reserves(client, pair_address(quote, WBNB)).await?,
+            },
+            Lookup::PairReserves(pair, meta) => Found::PairReserves {
+                pair,
+                meta,
+                reserves: read_reserves(client, pair).await?,
+            },
+            Lookup::AddToken(token) => Found::AddToken(discover(client, token).await),
+            Lookup::Chainlink(aggregator) => {
+                Found::Chainlink(read_chainlink(client, aggregator).await?)
+            }
+        })
+    }
+}
+
+/// Failures of a lookup and the earliest time it may run again
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Backoff {
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl Backoff {
+    /// Whether the lookup may run again at `now`
+    #[inline]
+    pub fn ready(&self, now: Instant) -> bool {
+        self.retry_at.is_none_or(|retry_at| now >= retry_at)
+    }
+
+    /// Record a failure at `now` and return the delay before the next attempt
+    #[inline]
+    pub fn fail(&mut self, now: Instant) -> Duration {
+        self.failures = self.failures.saturating_add(1);
+        let delay = backoff_delay(self.failures);
+        self.retry_at = Some(now + delay);
+        delay
+    }
+}
+
+/// Delay before the next attempt of a lookup that failed `failures` times in a row
+#[inline]
+pub fn backoff_delay(failures: u32) -> Duration {
+    let exponent = failures.saturating_sub(1).min(16);
+    LOOKUP_BACKOFF
+        .saturating_mul(1 << exponent)
+        .min(MAX_LOOKUP_BACKOFF)
+}
+
+/// Read the token manager view of a token
+#[inline]
+pub async fn read_curve_info(client: &DynProvider, token: Address) -> Result<CurveInfo, Error> {
+    let info = FourMemeContract::new(FOURMEME_CONTRACT, client)
+        ._tokenInfos(token)
+        .call()
+        .await?;
+
+    // The size of the curve is enough for progress when `K` and `T` cannot be used
+    let curve = BondingCurve::from_token_manager(&info).unwrap_or(BondingCurve {
+        max_offers: info.maxOffers,
+        max_funds: info.maxRaising,
+        ..Default::default()
+    });
+
+    Ok(CurveInfo {
+        quote: info.quote,
+        total_supply: info.totalSupply,
+        curve,
+        offers: info.offers,
+        funds: info.funds,
+        last_price: info.lastPrice,
+    })
+}
+
+/// Read the ERC-20 metadata of a token
+///
+/// Fails when `decimals()` fails, a missing symbol or name is left empty.
+#[inline]
+pub async fn read_metadata(client: &DynProvider, token: Address) -> Result<TokenMetadata, Error> {
+    let erc20 = IERC20::new(token, client);
+    let decimals = erc20.decimals().call().await?;
+    let symbol = erc20.symbol().call().await.unwrap_or_default();
+    let name = erc20.name().call().await.unwrap_or_default();
+
+    Ok(TokenMetadata {
+        decimals,
+        symbol,
+        name,
+    })
+}
+
+/// Read the reserves of a pair
+#[inline]
+pub async fn read_reserves(client: &DynProvider, pair: Address) -> Result<(U256, U256), Error> {
+    let reserves = PancakeSwapPair::new(pair, client)
+        .getReserves()
+        .call()
+        .await?;
+    Ok((U256::from(reserves.reserve0), U256::from(reserves.reserve1)))
+}
+
+/// Read the latest round of a Chainlink aggregator
+#[inline]
+pub async fn read_chainlink(
+    client: &DynProvider,
+    aggregator: Address,
+) -> Result<ChainlinkRound, Error> {
+    let feed = AggregatorV3Interface::new(aggregator, client);
+    let decimals = feed.decimals().call().await?;
+    let round = feed.latestRoundData().call().await?;
+
+    Ok(ChainlinkRound {
+        decimals,
+        round_id: round.roundId,
+        answer: round.answer,
+        updated_at: round.updatedAt,
+        answered_in_round: round.answeredInRound,
+    })
+}
+
+/// Look up a token discovered elsewhere: its metadata, its curve and its Pancake pair
+#[inline]
+pub async fn discover(client: &DynProvider, token: Address) -> Discovery {
+    let meta = EventMeta::at_block(client.get_block_number().await.unwrap_or_default());
+    let metadata = read_metadata(client, token).await.ok();
+
+    // Tokens unknown to the token manager read back as zeroes
+    let info = read_curve_info(client, token)
+        .await
+        .ok()
+        .filter(|info| !info.curve.max_offers.is_zero());
+
+    let quote = info.map_or(BNB_ADDRESS, |info| info.quote);
+    let quote_token = if quote == BNB_ADDRESS { WBNB } else { quote };
+    let pair = pair_address(token, quote_token);
+    let pair = read_reserves(client, pair)
+        .await
+        .ok()
+        .filter(|(reserve0, reserve1)| !reserve0.is_zero() && !reserve1.is_zero())
+        .map(|(reserve0, reserve1)| PairReserves {
+            pair,
+            is_token0: token < quote_token,
+            reserve0,
+            reserve1,
+        });
+
+    Discovery {
+        token,
+        meta,
+        metadata,
+        info,
+        pair,
+    }
+}
+
+#[test]
+fn test_backoff() {
+    assert_eq!(backoff_delay(1), LOOKUP_BACKOFF);
+    assert_eq!(backoff_delay(3), LOOKUP_BACKOFF * 4);
+    assert_eq!(backoff_delay(u32::MAX), MAX_LOOKUP_BACKOFF);
+
+    let now = Instant::now();
+    let mut backoff = Backoff::default();
+    assert!(backoff.ready(now));
+
+    assert_eq!(backoff.fail(now), LOOKUP_BACKOFF);
+    assert!(!backoff.ready(now));
+    assert!(backoff.ready(now + LOOKUP_BACKOFF));
+
+    assert_eq!(backoff.fail(now), LOOKUP_BACKOFF * 2);
+    assert!(!backoff.ready(now + LOOKUP_BACKOFF));
+}
+
+#[test]
+fn test_chainlink_round() {
+    let heartbeat = Duration::from_secs(300);
+    let round = ChainlinkRound {
+        decimals: 8,
+        round_id: U80::from(10),
+        answer: I256::try_from(600_00000000i64).unwrap(),
+        updated_at: U256::from(1_000u64),
+        answered_in_round: U80::from(10),
+    };
+    assert_eq!(
+        round.usd_per_bnb(heartbeat, 1_100).unwrap(),
+        U256::from(600u64) * pow10(18)
+    );
+
+    // Older than the heartbeat
+    assert!(round.usd_per_bnb(heartbeat, 1_301).is_err());
+
+    // Carried over from an earlier round
+    let carried = ChainlinkRound {
+        answered_in_round: U80::from(9),
+        ..round
+    };
+    assert!(carried.usd_per_bnb(heartbeat, 1_100).is_err());
+
+    let negative = ChainlinkRound {
+        answer: I256::MINUS_ONE,
+        ..round
+    };
+    assert!(negative.usd_per_bnb(heartbeat, 1_100).is_err());
+}
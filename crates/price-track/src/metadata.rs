@@ -0,0 +1,22 @@
+/// Decimals assumed for tokens whose `decimals()` cannot be read
+pub const DEFAULT_DECIMALS: u8 = 18;
+
+/// ERC-20 metadata of a tracked token or quote asset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMetadata {
+    pub decimals: u8,
+    /// Empty when the token does not return a string symbol
+    pub symbol: String,
+    /// Empty when the token does not return a string name
+    pub name: String,
+}
+
+impl Default for TokenMetadata {
+    fn default() -> Self {
+        Self {
+            decimals: DEFAULT_DECIMALS,
+            symbol: String::new(),
+            name: String::new(),
+        }
+    }
+}
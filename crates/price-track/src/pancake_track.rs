@@ -14,13 +14,18 @@ use rpc::Rpc;
 use tokio::sync::mpsc;
 use tracing::{error, info};
 
+use crate::snapshot::EventMeta;
+
 pub struct PancakeTrack {
     rpc: Rpc,
-    tx: mpsc::UnboundedSender<(PancakeSwapEvent, Address)>,
+    tx: mpsc::UnboundedSender<(PancakeSwapEvent, Address, EventMeta)>,
 }
 
 impl PancakeTrack {
-    pub fn new(rpc: Rpc, tx: mpsc::UnboundedSender<(PancakeSwapEvent, Address)>) -> Self {
+    pub fn new(
+        rpc: Rpc,
+        tx: mpsc::UnboundedSender<(PancakeSwapEvent, Address, EventMeta)>,
+    ) -> Self {
         Self { rpc, tx }
     }
 
@@ -37,6 +42,7 @@ impl PancakeTrack {
         while let Some(log) = stream.next().await {
             // get pair address
             let pair_address = log.address();
+            let meta = EventMeta::from_log(&log);
 
             let log = match Log::new(
                 log.address(),
@@ -60,7 +66,7 @@ impl PancakeTrack {
             };
 
             // Send event and pair address through channel
-            if let Err(e) = self.tx.send((event, pair_address)) {
+            if let Err(e) = self.tx.send((event, pair_address, meta)) {
                 error!(?e, "Failed to send event through channel");
                 break;
             }
@@ -0,0 +1,95 @@
+use alloy::primitives::{Address, U256, address, uint};
+use std::time::Duration;
+
+/// BNB, as reported by Fourmeme for curves quoted in the native token
+pub const BNB_ADDRESS: Address = address!("0x0000000000000000000000000000000000000000");
+/// USDT
+pub const USDT: Address = address!("0x55d398326f99059fF775485246999027B3197955");
+/// BUSD
+pub const BUSD: Address = address!("0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56");
+/// USD1
+pub const USD1: Address = address!("0x8d0D000Ee44948FC98c9B98A4FA4921476f08B0d");
+/// CAKE
+pub const CAKE: Address = address!("0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82");
+/// Chainlink BNB/USD aggregator
+pub const CHAINLINK_BNB_USD: Address = address!("0x0567F2323251f0Aab15c8dFb1967E4e8A7D42aeE");
+/// Age past which a Chainlink answer is stale, a few heartbeats of the BNB/USD feed
+pub const DEFAULT_CHAINLINK_HEARTBEAT: Duration = Duration::from_secs(300);
+
+/// Quote assets pegged to 1 USD
+pub const STABLE_QUOTES: [Address; 3] = [USDT, BUSD, USD1];
+/// Quote assets tracked from startup, other quotes are added when first seen
+pub const KNOWN_QUOTES: [Address; 4] = [USDT, BUSD, USD1, CAKE];
+
+/// Fixed-point scale of prices and rates (1e18)
+pub const PRICE_SCALE: U256 = uint!(1_000_000_000_000_000_000_U256);
+
+/// Exchange rates of a quote asset, per whole quote token scaled by 1e18
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuoteRate {
+    /// BNB wei per quote token
+    pub wei_per_quote: U256,
+    /// USD per quote token
+    pub usd_per_quote: U256,
+}
+
+/// Whether `quote` is pegged to 1 USD
+#[inline]
+pub fn is_stable(quote: &Address) -> bool {
+    STABLE_QUOTES.contains(quote)
+}
+
+/// `amount * numerator / denominator`, zero when `denominator` is zero and saturating at
+/// `U256::MAX` instead of overflowing
+#[inline]
+pub fn mul_div(amount: U256, numerator: U256, denominator: U256) -> U256 {
+    if denominator.is_zero() {
+        return U256::ZERO;
+    }
+    match amount.checked_mul(numerator) {
+        Some(product) => product / denominator,
+        // Divide first, losing precision only for extreme values
+        None => (amount / denominator).saturating_mul(numerator),
+    }
+}
+
+/// `amount * rate / 1e18`
+#[inline]
+pub fn mul_scaled(amount: U256, rate: U256) -> U256 {
+    mul_div(amount, rate, PRICE_SCALE)
+}
+
+/// `amount * 1e18 / divisor`
+#[inline]
+pub fn div_scaled(amount: U256, divisor: U256) -> U256 {
+    mul_div(amount, PRICE_SCALE, divisor)
+}
+
+/// `10^decimals`
+#[inline]
+pub fn pow10(decimals: u8) -> U256 {
+    U256::from(10).pow(U256::from(decimals))
+}
+
+/// Price of one whole base token in whole quote tokens, scaled by 1e18
+///
+/// # Arguments
+///
+/// * `reserve_base` - The reserve of the priced token, in its smallest unit
+/// * `base_decimals` - The decimals of the priced token
+/// * `reserve_quote` - The reserve of the quote token, in its smallest unit
+/// * `quote_decimals` - The decimals of the quote token
+///
+#[inline]
+pub fn price_from_reserves(
+    reserve_base: U256,
+    base_decimals: u8,
+    reserve_quote: U256,
+    quote_decimals: u8,
+) -> U256 {
+    mul_div(
+        reserve_quote.saturating_mul(pow10(base_decimals)),
+        PRICE_SCALE,
+        reserve_base.saturating_mul(pow10(quote_decimals)),
+    )
+}
@@ -0,0 +1,119 @@
+use alloy::{
+    primitives::{Address, U256},
+    rpc::types::Log,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+use types::{PriceResponse, PriceSource, TokenStatus};
+
+/// Block context of a tracked event
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct EventMeta {
+    pub block_number: u64,
+    /// Block timestamp in seconds, or the receive time when the node omits it
+    pub timestamp: u64,
+}
+
+impl EventMeta {
+    #[inline]
+    pub fn from_log(log: &Log) -> Self {
+        Self {
+            block_number: log.block_number.unwrap_or_default(),
+            timestamp: log.block_timestamp.unwrap_or_else(now),
+        }
+    }
+
+    /// Context of a price read at `block_number` rather than taken from an event
+    #[inline]
+    pub fn at_block(block_number: u64) -> Self {
+        Self {
+            block_number,
+            timestamp: now(),
+        }
+    }
+}
+
+/// Current unix time in seconds
+#[inline]
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Venue and reserves behind the latest price of a token
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriceUpdate {
+    pub source: PriceSource,
+    pub meta: EventMeta,
+    /// Pair reserve of the token, or tokens left on the curve
+    pub reserve_token: U256,
+    /// Pair reserve of the quote, or funds raised by the curve
+    pub reserve_quote: U256,
+}
+
+/// Everything the tracker knows about the price of a token
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriceSnapshot {
+    pub token: Address,
+    pub status: TokenStatus,
+    pub quote: Address,
+    /// Pancake pair of the token, zero while on the curve
+    pub pair: Address,
+    pub wei_per_token: U256,
+    pub usd_per_token: U256,
+    pub usd_market_cap: U256,
+    pub update: PriceUpdate,
+    /// Curve progress in basis points, 10_000 once migrated
+    pub progress_bps: u16,
+}
+
+impl From<&PriceSnapshot> for PriceResponse {
+    fn from(snapshot: &PriceSnapshot) -> Self {
+        Self {
+            token_address: snapshot.token.0.0,
+            status: snapshot.status,
+            source: snapshot.update.source,
+            wei_per_token: snapshot.wei_per_token.to_be_bytes(),
+            usd_per_token: snapshot.usd_per_token.to_be_bytes(),
+            usd_market_cap: snapshot.usd_market_cap.to_be_bytes(),
+            block_number: snapshot.update.meta.block_number,
+            timestamp: snapshot.update.meta.timestamp,
+            quote_token: snapshot.quote.0.0,
+            pair_address: snapshot.pair.0.0,
+            reserve_token: snapshot.update.reserve_token.to_be_bytes(),
+            reserve_quote: snapshot.update.reserve_quote.to_be_bytes(),
+            progress_bps: snapshot.progress_bps,
+            is_token0: false,
+        }
+    }
+}
+
+/// Pancake pair a token is priced from, answered to `GetPair`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PairSnapshot {
+    pub token: Address,
+    pub status: TokenStatus,
+    pub pair: Address,
+    pub quote: Address,
+    pub is_token0: bool,
+    /// Latest reserves of the pair, default until a `Sync` event or reserve read
+    pub update: PriceUpdate,
+}
+
+impl From<&PairSnapshot> for PriceResponse {
+    fn from(snapshot: &PairSnapshot) -> Self {
+        Self {
+            token_address: snapshot.token.0.0,
+            status: snapshot.status,
+            source: snapshot.update.source,
+            block_number: snapshot.update.meta.block_number,
+            timestamp: snapshot.update.meta.timestamp,
+            quote_token: snapshot.quote.0.0,
+            pair_address: snapshot.pair.0.0,
+            reserve_token: snapshot.update.reserve_token.to_be_bytes(),
+            reserve_quote: snapshot.update.reserve_quote.to_be_bytes(),
+            is_token0: snapshot.is_token0,
+            ..Default::default()
+        }
+    }
+}
@@ -1,18 +1,30 @@
+pub mod nonce;
+pub mod tracker;
+pub mod wallet;
+
 use alloy::hex;
+use alloy::primitives::Address;
 use alloy::primitives::FixedBytes;
 use alloy::providers::Provider; // bring Provider trait into scope for methods like get_gas_price
-use alloy::{primitives::Address, signers::local::PrivateKeySigner};
 use alloy::{
     providers::{DynProvider, IpcConnect, ProviderBuilder, WsConnect},
     transports::http::reqwest::Url,
 };
 use anyhow::{Context, Error, Result};
+use futures::future::try_join_all;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 use tokio::time::sleep;
 
+use crate::{nonce::NonceManager, wallet::WalletPool};
+
+/// BSC mainnet chain id
+pub const BSC_CHAIN_ID: u64 = 56;
+/// BSC testnet chain id
+pub const BSC_TESTNET_CHAIN_ID: u64 = 97;
+
 /// The type of connection to use for the RPC client.
 #[derive(Debug)]
 pub enum ConnectType {
@@ -26,38 +38,92 @@ pub enum ConnectType {
 pub struct Rpc {
     pub client: DynProvider,
     pub sender_address: Address,
+    pub chain_id: u64,
     pub gas_price: Arc<Mutex<u128>>,
-    pub nonce: Arc<Mutex<u64>>,
+    pub nonce: Arc<NonceManager>,
+    pub wallets: Arc<WalletPool>,
 }
 
 impl Rpc {
-    pub async fn init(connect_type: ConnectType, private_key: &str) -> Result<Self, Error> {
-        let signer: PrivateKeySigner = private_key.parse()?;
-        let sender_address = signer.address();
+    /// Connect to the node and verify it serves the expected chain
+    ///
+    /// # Arguments
+    ///
+    /// * `connect_type` - The connection to use
+    /// * `private_key` - The private key of the sending wallet
+    /// * `expected_chain_id` - The chain id the node must report, e.g. `BSC_CHAIN_ID`
+    ///
+    /// # Note
+    ///
+    /// The chain id is fetched once here and cached, so signing never needs a round-trip.
+    pub async fn init(
+        connect_type: ConnectType,
+        private_key: &str,
+        expected_chain_id: u64,
+    ) -> Result<Self, Error> {
+        Self::init_with_wallets(connect_type, &[private_key], expected_chain_id).await
+    }
+
+    /// Connect to the node with several wallets sharing one provider
+    ///
+    /// # Arguments
+    ///
+    /// * `connect_type` - The connection to use
+    /// * `private_keys` - The private keys of the wallets, the first one being the primary
+    /// * `expected_chain_id` - The chain id the node must report, e.g. `BSC_CHAIN_ID`
+    pub async fn init_with_wallets(
+        connect_type: ConnectType,
+        private_keys: &[&str],
+        expected_chain_id: u64,
+    ) -> Result<Self, Error> {
+        let signers = WalletPool::parse_keys(private_keys)?;
+        let wallet = WalletPool::ethereum_wallet(&signers);
         let provider = match connect_type {
             ConnectType::Http(url) => ProviderBuilder::new()
-                .wallet(signer)
+                .wallet(wallet)
                 .connect_http(url.parse::<Url>().with_context(|| "Invalid HTTP URL")?),
             ConnectType::Ws(url) => ProviderBuilder::new()
-                .wallet(signer)
+                .wallet(wallet)
                 .connect_ws(WsConnect::new(&url))
                 .await
-                .with_context(|| format!("Failed to connect to WebSocket"))?,
+                .with_context(|| "Failed to connect to WebSocket")?,
             ConnectType::Ipc(path) => ProviderBuilder::new()
-                .wallet(signer)
+                .wallet(wallet)
                 .connect_ipc(IpcConnect::new(path))
                 .await
-                .with_context(|| format!("Failed to connect to IPC"))?,
+                .with_context(|| "Failed to connect to IPC")?,
         };
 
+        let chain_id = provider
+            .get_chain_id()
+            .await
+            .with_context(|| "Failed to fetch chain id")?;
+        if chain_id != expected_chain_id {
+            return Err(Error::msg(format!(
+                "Chain id mismatch: expected {}, node reports {}",
+                expected_chain_id, chain_id
+            )));
+        }
+
+        let client = DynProvider::new(provider);
+        let wallets = Arc::new(WalletPool::new(signers, chain_id)?);
+        // Start every wallet from its pending count rather than zero
+        try_join_all(wallets.iter().map(|wallet| wallet.nonce.sync(&client))).await?;
+
+        let primary = wallets.primary();
+        let sender_address = primary.address();
+        // The primary wallet shares its nonce manager with `Rpc::nonce`
+        let nonce = Arc::clone(&primary.nonce);
+
         let gas_price = Arc::new(Mutex::new(1000000000));
-        let nonce = Arc::new(Mutex::new(0));
 
         Ok(Self {
-            client: DynProvider::new(provider),
+            client,
             sender_address,
+            chain_id,
             gas_price,
             nonce,
+            wallets,
         })
     }
 
@@ -67,19 +133,18 @@ impl Rpc {
         *self.gas_price.lock().await
     }
 
-    /// Get the current nonce from cache
+    /// Get the nonce the next reservation will get
     #[inline]
     pub async fn get_nonce(&self) -> u64 {
-        *self.nonce.lock().await
+        self.nonce.peek().await
     }
 
-    /// Increment and get the next nonce (for sending multiple transactions)
+    /// Reserve the next nonce (for sending multiple transactions)
+    ///
+    /// The nonce must be confirmed or released through `nonce` once submission is known.
     #[inline]
     pub async fn get_and_increment_nonce(&self) -> u64 {
-        let mut nonce = self.nonce.lock().await;
-        let current = *nonce;
-        *nonce += 1;
-        current
+        self.nonce.reserve().await
     }
 
     /// Send a raw signed transaction to the network
@@ -147,7 +212,7 @@ impl Rpc {
         Ok(task)
     }
 
-    /// Start a background task to update nonce periodically
+    /// Start a background task to sync the nonce periodically
     ///
     /// # Arguments
     ///
@@ -158,20 +223,16 @@ impl Rpc {
     /// * `JoinHandle` - The handle to the background task
     #[inline]
     pub async fn update_nonce(&self, interval: Duration) -> Result<JoinHandle<()>, Error> {
-        // Spawn a background task to refresh nonce periodically
+        // Spawn a background task to sync nonce periodically
         let client_cloned = self.client.clone();
-        let sender_address = self.sender_address;
-        let nonce_cloned: Arc<Mutex<u64>> = Arc::clone(&self.nonce);
+        let nonce_cloned: Arc<NonceManager> = Arc::clone(&self.nonce);
         let task = tokio::spawn(async move {
             loop {
-                match client_cloned.get_transaction_count(sender_address).await {
-                    Ok(count) => {
-                        let mut lock = nonce_cloned.lock().await;
-                        // Only update if the on-chain nonce is higher (prevents going backwards)
-                        if count > *lock {
-                            *lock = count;
-                        }
+                match nonce_cloned.sync(&client_cloned).await {
+                    Ok(gaps) if !gaps.is_empty() => {
+                        eprintln!("nonce gaps detected: {:?}", gaps);
                     }
+                    Ok(_) => {}
                     Err(e) => {
                         eprintln!("failed to fetch nonce: {:?}", e);
                     }
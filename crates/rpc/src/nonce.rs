@@ -0,0 +1,278 @@
+use alloy::{
+    network::TransactionBuilder,
+    primitives::{Address, B256, U256},
+    providers::{DynProvider, Provider},
+    rpc::types::TransactionRequest,
+};
+use anyhow::{Context, Error, Result};
+use sender::Sender;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// How long a submitted nonce may stay out of the node's pending count before it is
+/// reported as a gap
+pub const DEFAULT_SUBMITTED_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Default)]
+struct NonceState {
+    /// Next never-used nonce
+    next: u64,
+    /// Latest pending count reported by the node, every nonce below it is used
+    pending: u64,
+    /// Reserved and not yet confirmed or released
+    in_flight: BTreeSet<u64>,
+    /// Released by callers, handed out again before `next`
+    released: BTreeSet<u64>,
+    /// Confirmed as submitted but not yet seen in the pending count, with the submit time
+    submitted: BTreeMap<u64, Instant>,
+}
+
+/// Nonce manager for one wallet with reservation, rollback and gap detection
+///
+/// Every nonce handed out by `reserve` must be either `confirm`ed once the transaction
+/// was accepted by the node or a relay, or `release`d if submission failed, so that it
+/// can be reused instead of leaving a hole.
+#[derive(Debug)]
+pub struct NonceManager {
+    address: Address,
+    submitted_timeout: Duration,
+    state: Mutex<NonceState>,
+}
+
+impl NonceManager {
+    #[inline]
+    pub fn new(address: Address, start: u64) -> Self {
+        Self {
+            address,
+            submitted_timeout: DEFAULT_SUBMITTED_TIMEOUT,
+            state: Mutex::new(NonceState {
+                next: start,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Override how long a submitted nonce may stay out of the pending count, 60 s by default
+    ///
+    /// A transaction sent to a private relay and dropped there never reaches the node, so
+    /// its nonce is only reported as a gap once this timeout has passed.
+    #[inline]
+    pub fn with_submitted_timeout(mut self, timeout: Duration) -> Self {
+        self.submitted_timeout = timeout;
+        self
+    }
+
+    #[inline]
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// The nonce the next reservation will get, without reserving it
+    #[inline]
+    pub async fn peek(&self) -> u64 {
+        let state = self.state.lock().await;
+        state.released.first().copied().unwrap_or(state.next)
+    }
+
+    /// Reserve a nonce, preferring the lowest released one
+    #[inline]
+    pub async fn reserve(&self) -> u64 {
+        let mut state = self.state.lock().await;
+        let nonce = match state.released.pop_first() {
+            Some(nonce) => nonce,
+            None => {
+                let nonce = state.next;
+                state.next += 1;
+                nonce
+            }
+        };
+        state.in_flight.insert(nonce);
+        nonce
+    }
+
+    /// Mark a reserved nonce as submitted
+    #[inline]
+    pub async fn confirm(&self, nonce: u64) {
+        let mut state = self.state.lock().await;
+        state.in_flight.remove(&nonce);
+        state.submitted.insert(nonce, Instant::now());
+    }
+
+    /// Give back a reserved nonce whose transaction was never submitted
+    ///
+    /// Nonces below the latest pending count are already used on chain and are not
+    /// handed out again.
+    #[inline]
+    pub async fn release(&self, nonce: u64) {
+        let mut guard = self.state.lock().await;
+        let state = &mut *guard;
+        if !state.in_flight.remove(&nonce) || nonce < state.pending {
+            return;
+        }
+        state.released.insert(nonce);
+
+        // Roll `next` back over released nonces at the top
+        while state.next > 0 && state.released.remove(&(state.next - 1)) {
+            state.next -= 1;
+        }
+    }
+
+    /// Reconcile with the pending transaction count reported by the node
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<u64>` - Nonces below `next` that are neither pending on chain nor in flight,
+    ///   including nonces submitted longer ago than the submitted timeout
+    #[inline]
+    pub async fn sync(&self, client: &DynProvider) -> Result<Vec<u64>, Error> {
+        let pending = client
+            .get_transaction_count(self.address)
+            .pending()
+            .await
+            .with_context(|| format!("Failed to fetch nonce of {}", self.address))?;
+
+        Ok(self.apply_pending_count(pending, Instant::now()).await)
+    }
+
+    async fn apply_pending_count(&self, pending: u64, now: Instant) -> Vec<u64> {
+        let mut state = self.state.lock().await;
+
+        // Everything below the pending count is used on chain
+        state.pending = state.pending.max(pending);
+        state.released.retain(|nonce| *nonce >= pending);
+        state.submitted.retain(|nonce, submitted_at| {
+            // Still unseen after the timeout: dropped, e.g. by a private relay
+            *nonce >= pending && now.duration_since(*submitted_at) < self.submitted_timeout
+        });
+        if pending > state.next {
+            state.next = pending;
+        }
+
+        (pending..state.next)
+            .filter(|nonce| {
+                !state.in_flight.contains(nonce) && !state.submitted.contains_key(nonce)
+            })
+            .collect()
+    }
+
+    /// Send a zero-value self-transfer to consume `nonce`
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The provider to submit with
+    /// * `sender` - The signer of this wallet
+    /// * `nonce` - The nonce to consume; also replaces a stuck transaction when the gas price is higher
+    /// * `gas_price` - Gas price in wei
+    #[inline]
+    pub async fn cancel(
+        &self,
+        client: &DynProvider,
+        sender: &Sender,
+        nonce: u64,
+        gas_price: u128,
+    ) -> Result<B256, Error> {
+        let cancel_tx = TransactionRequest::default()
+            .with_to(self.address)
+            .with_value(U256::ZERO)
+            .with_gas_limit(21000)
+            .with_gas_price(gas_price)
+            .with_nonce(nonce);
+        let signed = sender.sign(cancel_tx).await?;
+
+        let _ = client
+            .send_raw_transaction(&signed.raw)
+            .await
+            .with_context(|| format!("Failed to send cancel transaction for nonce {}", nonce))?;
+
+        let mut state = self.state.lock().await;
+        state.released.remove(&nonce);
+        state.in_flight.remove(&nonce);
+        state.submitted.insert(nonce, Instant::now());
+        if nonce >= state.next {
+            state.next = nonce + 1;
+        }
+
+        Ok(signed.hash)
+    }
+
+    /// Detect gaps and fill every one of them with a cancel transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<B256>` - The hashes of the cancel transactions
+    #[inline]
+    pub async fn fill_gaps(
+        &self,
+        client: &DynProvider,
+        sender: &Sender,
+        gas_price: u128,
+    ) -> Result<Vec<B256>, Error> {
+        let gaps = self.sync(client).await?;
+        let mut hashes = Vec::with_capacity(gaps.len());
+        for nonce in gaps {
+            hashes.push(self.cancel(client, sender, nonce, gas_price).await?);
+        }
+        Ok(hashes)
+    }
+}
+
+#[tokio::test]
+async fn test_reserve_release_and_gaps() {
+    let manager = NonceManager::new(Address::ZERO, 5);
+    let now = Instant::now();
+
+    assert_eq!(manager.reserve().await, 5);
+    assert_eq!(manager.reserve().await, 6);
+    assert_eq!(manager.reserve().await, 7);
+
+    // Releasing the top nonce rolls `next` back
+    manager.release(7).await;
+    assert_eq!(manager.peek().await, 7);
+
+    // Releasing a nonce in the middle leaves a hole that is handed out first
+    manager.confirm(6).await;
+    manager.release(5).await;
+    assert_eq!(manager.peek().await, 5);
+
+    // Node still reports 5 pending: nonce 5 is a gap, 6 is queued behind it
+    assert_eq!(manager.apply_pending_count(5, now).await, vec![5]);
+
+    assert_eq!(manager.reserve().await, 5);
+    assert_eq!(manager.apply_pending_count(5, now).await, Vec::<u64>::new());
+    manager.confirm(5).await;
+
+    // Both mined
+    assert_eq!(manager.apply_pending_count(7, now).await, Vec::<u64>::new());
+    assert_eq!(manager.reserve().await, 7);
+}
+
+#[tokio::test]
+async fn test_submitted_expiry_and_stale_release() {
+    let timeout = Duration::from_secs(30);
+    let manager = NonceManager::new(Address::ZERO, 0).with_submitted_timeout(timeout);
+
+    assert_eq!(manager.reserve().await, 0);
+    assert_eq!(manager.reserve().await, 1);
+    manager.confirm(0).await;
+    let submitted_at = Instant::now();
+
+    // Submitted privately and never seen by the node: a gap once the timeout passed
+    assert_eq!(
+        manager.apply_pending_count(0, submitted_at).await,
+        Vec::<u64>::new()
+    );
+    assert_eq!(
+        manager.apply_pending_count(0, submitted_at + timeout).await,
+        vec![0]
+    );
+
+    // Nonce 1 was used on chain meanwhile, releasing it must not hand it out again
+    assert_eq!(
+        manager.apply_pending_count(2, submitted_at).await,
+        Vec::<u64>::new()
+    );
+    manager.release(1).await;
+    assert_eq!(manager.peek().await, 2);
+    assert_eq!(manager.reserve().await, 2);
+}
@@ -0,0 +1,562 @@
+use alloy::{
+    network::TransactionBuilder,
+    primitives::{Address, B256, U256},
+    providers::{DynProvider, Provider},
+    rpc::types::{TransactionReceipt, TransactionRequest},
+};
+use anyhow::{Context, Error, Result};
+use async_trait::async_trait;
+use sender::{Sender, SignedTransaction};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+/// Polls in a row without the node knowing any of the hashes before a transaction is dropped
+const DROPPED_AFTER_POLLS: u32 = 10;
+
+/// Time after the latest private submission before a transaction the node never mined is dropped
+pub const DEFAULT_PRIVATE_DROP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Broadcasts signed transactions, e.g. to the node or through a private relay
+#[async_trait]
+pub trait TxSubmitter: Send + Sync {
+    /// Whether submissions stay out of the public mempool until they are mined
+    fn is_private(&self) -> bool;
+
+    /// Broadcast a signed transaction
+    async fn submit_signed(&self, signed: &SignedTransaction) -> Result<(), Error>;
+}
+
+#[async_trait]
+impl TxSubmitter for DynProvider {
+    fn is_private(&self) -> bool {
+        false
+    }
+
+    async fn submit_signed(&self, signed: &SignedTransaction) -> Result<(), Error> {
+        let _ = self
+            .send_raw_transaction(&signed.raw)
+            .await
+            .with_context(|| format!("Failed to send transaction {}", signed.hash))?;
+        Ok(())
+    }
+}
+
+/// Final state of a tracked transaction
+#[derive(Debug, Clone)]
+pub enum TxStatus {
+    /// One of the tracked hashes was mined
+    Mined(Box<TransactionReceipt>),
+    /// The nonce was consumed by a transaction that is not tracked
+    Replaced,
+    /// The node no longer knows any of the tracked hashes
+    Dropped,
+    /// Still pending when the timeout elapsed
+    Pending,
+}
+
+/// Result of tracking a transaction
+#[derive(Debug, Clone)]
+pub struct TxOutcome {
+    pub status: TxStatus,
+    /// The hash that was mined, or the latest submitted one
+    pub hash: B256,
+    /// Blocks between submission and inclusion
+    pub inclusion_blocks: u64,
+    /// Time between submission and detection of the final state
+    pub latency: Duration,
+}
+
+/// A submitted transaction and every replacement sharing its nonce
+#[derive(Debug, Clone)]
+pub struct TrackedTx {
+    pub from: Address,
+    pub nonce: u64,
+    /// The latest request, used to build replacements
+    pub request: TransactionRequest,
+    /// The latest submitted hash
+    pub hash: B256,
+    /// Hashes replaced by a later submission for this nonce, oldest first
+    pub replaced_hashes: Vec<B256>,
+    /// Sent through a private relay, the node may not know it before it is mined
+    pub private: bool,
+    pub submitted_at: Instant,
+    /// When the latest hash was sent
+    pub sent_at: Instant,
+    pub submitted_block: u64,
+}
+
+impl TrackedTx {
+    /// All hashes sent for this nonce, newest first
+    #[inline]
+    pub fn hashes(&self) -> impl Iterator<Item = &B256> {
+        std::iter::once(&self.hash).chain(self.replaced_hashes.iter().rev())
+    }
+}
+
+/// Replacement behaviour while watching a transaction
+#[derive(Debug, Clone)]
+pub struct ReplacementPolicy {
+    /// Time to wait before each speed-up
+    pub speed_up_after: Duration,
+    /// Maximum number of speed-ups
+    pub max_speed_ups: u32,
+    /// Gas price increase per replacement, in percent (BSC nodes require at least 10)
+    pub bump_percent: u64,
+    /// Cancel with a self-transfer once all speed-ups are exhausted
+    pub cancel: bool,
+}
+
+impl Default for ReplacementPolicy {
+    fn default() -> Self {
+        Self {
+            speed_up_after: Duration::from_secs(6),
+            max_speed_ups: 2,
+            bump_percent: 15,
+            cancel: true,
+        }
+    }
+}
+
+/// Follows submitted transactions until they are mined, dropped or replaced
+#[derive(Clone, Debug)]
+pub struct TxTracker {
+    client: DynProvider,
+    poll_interval: Duration,
+    private_drop_timeout: Duration,
+}
+
+impl TxTracker {
+    #[inline]
+    pub fn new(client: DynProvider) -> Self {
+        Self {
+            client,
+            poll_interval: Duration::from_millis(250),
+            private_drop_timeout: DEFAULT_PRIVATE_DROP_TIMEOUT,
+        }
+    }
+
+    #[inline]
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Override how long a private transaction may stay unmined after its latest submission
+    /// before it is reported as dropped, 60 s by default
+    ///
+    /// Public transactions are dropped once the node no longer knows any of their hashes.
+    #[inline]
+    pub fn with_private_drop_timeout(mut self, timeout: Duration) -> Self {
+        self.private_drop_timeout = timeout;
+        self
+    }
+
+    /// Start tracking a transaction that was already submitted, e.g. through Bloxroute
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The request the transaction was signed from, with `from` and `nonce` set
+    /// * `hash` - The hash returned on submission
+    /// * `private` - Whether it was sent through a private relay, see `TrackedTx::private`
+    #[inline]
+    pub async fn track_submitted(
+        &self,
+        request: TransactionRequest,
+        hash: B256,
+        private: bool,
+    ) -> Result<TrackedTx, Error> {
+        let from = request
+            .from
+            .ok_or_else(|| Error::msg("Tracked request has no sender"))?;
+        let nonce = request
+            .nonce
+            .ok_or_else(|| Error::msg("Tracked request has no nonce"))?;
+        let submitted_block = self.client.get_block_number().await?;
+        let submitted_at = Instant::now();
+
+        Ok(TrackedTx {
+            from,
+            nonce,
+            request,
+            hash,
+            replaced_hashes: Vec::new(),
+            private,
+            submitted_at,
+            sent_at: submitted_at,
+            submitted_block,
+        })
+    }
+
+    /// Sign a request, send it through `submitter` and start tracking it
+    ///
+    /// # Arguments
+    ///
+    /// * `submitter` - The node client or a private relay
+    #[inline]
+    pub async fn submit(
+        &self,
+        sender: &Sender,
+        submitter: &dyn TxSubmitter,
+        request: TransactionRequest,
+    ) -> Result<TrackedTx, Error> {
+        let request = request.with_from(sender.address());
+        let signed = sender.sign(request.clone()).await?;
+        submitter.submit_signed(&signed).await?;
+        self.track_submitted(request, signed.hash, submitter.is_private())
+            .await
+    }
+
+    /// Replace the transaction with the same call at a higher gas price
+    ///
+    /// # Arguments
+    ///
+    /// * `submitter` - Where the replacement is sent, the relay of a private transaction
+    #[inline]
+    pub async fn speed_up(
+        &self,
+        sender: &Sender,
+        submitter: &dyn TxSubmitter,
+        tracked: &mut TrackedTx,
+        bump_percent: u64,
+    ) -> Result<B256, Error> {
+        let request = bump_gas(tracked.request.clone(), bump_percent);
+        self.replace(sender, submitter, tracked, request).await
+    }
+
+    /// Replace the transaction with a zero-value self-transfer at a higher gas price
+    ///
+    /// # Arguments
+    ///
+    /// * `submitter` - Where the replacement is sent, the relay of a private transaction
+    #[inline]
+    pub async fn cancel(
+        &self,
+        sender: &Sender,
+        submitter: &dyn TxSubmitter,
+        tracked: &mut TrackedTx,
+        bump_percent: u64,
+    ) -> Result<B256, Error> {
+        let mut request = TransactionRequest::default()
+            .with_from(tracked.from)
+            .with_to(tracked.from)
+            .with_value(U256::ZERO)
+            .with_nonce(tracked.nonce)
+            .with_gas_limit(21000);
+        request.gas_price = tracked.request.gas_price;
+        request.max_fee_per_gas = tracked.request.max_fee_per_gas;
+        request.max_priority_fee_per_gas = tracked.request.max_priority_fee_per_gas;
+
+        let request = bump_gas(request, bump_percent);
+        self.replace(sender, submitter, tracked, request).await
+    }
+
+    /// Poll until the transaction reaches a final state or `timeout` elapses
+    ///
+    /// A private transaction is only reported as dropped once the private drop timeout
+    /// has passed since its latest submission, the node not knowing it proves nothing.
+    #[inline]
+    pub async fn track(&self, tracked: &TrackedTx, timeout: Duration) -> Result<TxOutcome, Error> {
+        let started = Instant::now();
+        let mut unknown_polls = 0;
+
+        loop {
+            if let Some(outcome) = self.find_receipt(tracked).await? {
+                return Ok(outcome);
+            }
+
+            let mined_nonce = self
+                .client
+                .get_transaction_count(tracked.from)
+                .latest()
+                .await?;
+            if mined_nonce > tracked.nonce {
+                // The receipt may have landed between the two calls
+                if let Some(outcome) = self.find_receipt(tracked).await? {
+                    return Ok(outcome);
+                }
+                return Ok(self.outcome(tracked, TxStatus::Replaced, tracked.hash, 0));
+            }
+
+            if tracked.private {
+                if tracked.sent_at.elapsed() >= self.private_drop_timeout {
+                    return Ok(self.outcome(tracked, TxStatus::Dropped, tracked.hash, 0));
+                }
+            } else if self.is_known(tracked).await? {
+                unknown_polls = 0;
+            } else {
+                unknown_polls += 1;
+                if unknown_polls >= DROPPED_AFTER_POLLS {
+                    return Ok(self.outcome(tracked, TxStatus::Dropped, tracked.hash, 0));
+                }
+            }
+
+            if started.elapsed() >= timeout {
+                return Ok(self.outcome(tracked, TxStatus::Pending, tracked.hash, 0));
+            }
+
+            sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Track the transaction, speeding it up and finally cancelling it per `policy`
+    ///
+    /// Replacements are sent through `submitter`, pass the relay of a private transaction
+    /// so it does not leak to the public mempool.
+    #[inline]
+    pub async fn watch(
+        &self,
+        sender: &Sender,
+        submitter: &dyn TxSubmitter,
+        mut tracked: TrackedTx,
+        policy: &ReplacementPolicy,
+    ) -> Result<TxOutcome, Error> {
+        let mut speed_ups = 0;
+        let mut cancelled = false;
+
+        loop {
+            let outcome = self.track(&tracked, policy.speed_up_after).await?;
+            if !matches!(outcome.status, TxStatus::Pending) {
+                return Ok(outcome);
+            }
+
+            if speed_ups < policy.max_speed_ups {
+                speed_ups += 1;
+                self.speed_up(sender, submitter, &mut tracked, policy.bump_percent)
+                    .await?;
+            } else if policy.cancel && !cancelled {
+                cancelled = true;
+                self.cancel(sender, submitter, &mut tracked, policy.bump_percent)
+                    .await?;
+            } else {
+                return Ok(outcome);
+            }
+        }
+    }
+
+    async fn replace(
+        &self,
+        sender: &Sender,
+        submitter: &dyn TxSubmitter,
+        tracked: &mut TrackedTx,
+        request: TransactionRequest,
+    ) -> Result<B256, Error> {
+        let signed = sender.sign(request.clone()).await?;
+        submitter.submit_signed(&signed).await?;
+        tracked.request = request;
+        tracked.replaced_hashes.push(tracked.hash);
+        tracked.hash = signed.hash;
+        tracked.private |= submitter.is_private();
+        tracked.sent_at = Instant::now();
+        Ok(signed.hash)
+    }
+
+    async fn find_receipt(&self, tracked: &TrackedTx) -> Result<Option<TxOutcome>, Error> {
+        for hash in tracked.hashes() {
+            if let Some(receipt) = self.client.get_transaction_receipt(*hash).await? {
+                let inclusion_blocks = receipt
+                    .block_number
+                    .unwrap_or(tracked.submitted_block)
+                    .saturating_sub(tracked.submitted_block);
+                return Ok(Some(self.outcome(
+                    tracked,
+                    TxStatus::Mined(Box::new(receipt)),
+                    *hash,
+                    inclusion_blocks,
+                )));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn is_known(&self, tracked: &TrackedTx) -> Result<bool, Error> {
+        for hash in tracked.hashes() {
+            if self.client.get_transaction_by_hash(*hash).await?.is_some() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    #[inline]
+    fn outcome(
+        &self,
+        tracked: &TrackedTx,
+        status: TxStatus,
+        hash: B256,
+        inclusion_blocks: u64,
+    ) -> TxOutcome {
+        TxOutcome {
+            status,
+            hash,
+            inclusion_blocks,
+            latency: tracked.submitted_at.elapsed(),
+        }
+    }
+}
+
+/// Raise every gas price field of `request` by `bump_percent`, rounding up
+#[inline]
+pub fn bump_gas(mut request: TransactionRequest, bump_percent: u64) -> TransactionRequest {
+    let bump = |price: u128| price + (price * bump_percent as u128).div_ceil(100);
+    request.gas_price = request.gas_price.map(bump);
+    request.max_fee_per_gas = request.max_fee_per_gas.map(bump);
+    request.max_priority_fee_per_gas = request.max_priority_fee_per_gas.map(bump);
+    request
+}
+
+#[test]
+fn test_bump_gas() {
+    let legacy = bump_gas(
+        TransactionRequest::default().with_gas_price(1_000_000_000),
+        10,
+    );
+    assert_eq!(legacy.gas_price, Some(1_100_000_000));
+
+    let eip1559 = bump_gas(
+        TransactionRequest::default()
+            .with_max_fee_per_gas(3)
+            .with_max_priority_fee_per_gas(1),
+        15,
+    );
+    assert_eq!(eip1559.max_fee_per_gas, Some(4));
+    assert_eq!(eip1559.max_priority_fee_per_gas, Some(2));
+    assert_eq!(eip1559.gas_price, None);
+}
+
+#[cfg(test)]
+struct RecordingSubmitter(std::sync::Mutex<Vec<B256>>);
+
+#[cfg(test)]
+#[async_trait]
+impl TxSubmitter for RecordingSubmitter {
+    fn is_private(&self) -> bool {
+        true
+    }
+
+    async fn submit_signed(&self, signed: &SignedTransaction) -> Result<(), Error> {
+        self.0.lock().unwrap().push(signed.hash);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn test_tracker() -> (TxTracker, alloy::providers::mock::Asserter) {
+    use alloy::providers::{ProviderBuilder, mock::Asserter};
+
+    let asserter = Asserter::new();
+    let client = ProviderBuilder::new()
+        .connect_mocked_client(asserter.clone())
+        .erased();
+    let tracker = TxTracker::new(client).with_poll_interval(Duration::ZERO);
+    (tracker, asserter)
+}
+
+#[cfg(test)]
+fn test_request() -> TransactionRequest {
+    TransactionRequest::default()
+        .with_from(Address::repeat_byte(1))
+        .with_to(Address::repeat_byte(2))
+        .with_nonce(7)
+        .with_gas_limit(21000)
+        .with_gas_price(1_000_000_000)
+}
+
+#[tokio::test]
+async fn test_track_drops_public_but_waits_for_private() {
+    use alloy::primitives::U64;
+
+    let (tracker, asserter) = test_tracker();
+
+    // Public: dropped once the node stopped knowing the hash for 10 polls
+    asserter.push_success(&U64::from(100));
+    let public = tracker
+        .track_submitted(test_request(), B256::repeat_byte(3), false)
+        .await
+        .unwrap();
+    for _ in 0..DROPPED_AFTER_POLLS {
+        asserter.push_success(&None::<()>);
+        asserter.push_success(&U64::from(7));
+        asserter.push_success(&None::<()>);
+    }
+    let outcome = tracker
+        .track(&public, Duration::from_secs(60))
+        .await
+        .unwrap();
+    assert!(matches!(outcome.status, TxStatus::Dropped));
+
+    // Private: the node not knowing the hash is expected, it stays pending
+    asserter.push_success(&U64::from(100));
+    let private = tracker
+        .track_submitted(test_request(), B256::repeat_byte(4), true)
+        .await
+        .unwrap();
+    asserter.push_success(&None::<()>);
+    asserter.push_success(&U64::from(7));
+    let outcome = tracker.track(&private, Duration::ZERO).await.unwrap();
+    assert!(matches!(outcome.status, TxStatus::Pending));
+
+    // ...until the private drop timeout passed
+    let tracker = tracker.with_private_drop_timeout(Duration::ZERO);
+    asserter.push_success(&None::<()>);
+    asserter.push_success(&U64::from(7));
+    let outcome = tracker
+        .track(&private, Duration::from_secs(60))
+        .await
+        .unwrap();
+    assert!(matches!(outcome.status, TxStatus::Dropped));
+
+    // Nonce consumed by a transaction that is not tracked
+    asserter.push_success(&None::<()>);
+    asserter.push_success(&U64::from(8));
+    asserter.push_success(&None::<()>);
+    let outcome = tracker
+        .track(&private, Duration::from_secs(60))
+        .await
+        .unwrap();
+    assert!(matches!(outcome.status, TxStatus::Replaced));
+    assert_eq!(outcome.hash, private.hash);
+}
+
+#[tokio::test]
+async fn test_watch_replaces_through_the_submitter() {
+    use alloy::primitives::U64;
+
+    let (tracker, asserter) = test_tracker();
+    let sender = Sender::new(
+        "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        56,
+    )
+    .unwrap();
+    let relay = RecordingSubmitter(std::sync::Mutex::new(Vec::new()));
+    let policy = ReplacementPolicy {
+        speed_up_after: Duration::ZERO,
+        max_speed_ups: 1,
+        bump_percent: 10,
+        cancel: true,
+    };
+
+    asserter.push_success(&U64::from(100));
+    let tracked = tracker
+        .submit(&sender, &relay, test_request())
+        .await
+        .unwrap();
+    assert!(tracked.private);
+
+    // Pending after the original, the speed-up and the cancel; the node is never sent
+    // anything, so a public broadcast would fail on the empty mock queue
+    for hashes in 1..=3 {
+        for _ in 0..hashes {
+            asserter.push_success(&None::<()>);
+        }
+        asserter.push_success(&U64::from(7));
+    }
+    let outcome = tracker
+        .watch(&sender, &relay, tracked, &policy)
+        .await
+        .unwrap();
+    assert!(matches!(outcome.status, TxStatus::Pending));
+
+    let sent = relay.0.lock().unwrap().clone();
+    assert_eq!(sent.len(), 3);
+    assert_eq!(outcome.hash, sent[2]);
+}
@@ -0,0 +1,254 @@
+use alloy::{
+    network::EthereumWallet,
+    primitives::{Address, U256},
+    providers::{DynProvider, Provider},
+    signers::local::PrivateKeySigner,
+};
+use anyhow::{Context, Error, Result};
+use futures::future::try_join_all;
+use sender::Sender;
+
+use crate::nonce::NonceManager;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+
+/// A signing wallet with its own nonce manager and cached balance
+#[derive(Debug)]
+pub struct Wallet {
+    pub sender: Sender,
+    pub nonce: Arc<NonceManager>,
+    pub balance: Arc<Mutex<U256>>,
+}
+
+impl Wallet {
+    #[inline]
+    pub fn new(sender: Sender) -> Self {
+        Self {
+            nonce: Arc::new(NonceManager::new(sender.address(), 0)),
+            sender,
+            balance: Arc::new(Mutex::new(U256::ZERO)),
+        }
+    }
+
+    #[inline]
+    pub fn address(&self) -> Address {
+        self.sender.address()
+    }
+
+    /// Get the nonce the next reservation will get
+    #[inline]
+    pub async fn get_nonce(&self) -> u64 {
+        self.nonce.peek().await
+    }
+
+    /// Reserve the next nonce (for sending multiple transactions)
+    ///
+    /// The nonce must be confirmed or released through `nonce` once submission is known.
+    #[inline]
+    pub async fn get_and_increment_nonce(&self) -> u64 {
+        self.nonce.reserve().await
+    }
+
+    /// Get the current balance from cache
+    #[inline]
+    pub async fn get_balance(&self) -> U256 {
+        *self.balance.lock().await
+    }
+
+    /// Sync the nonce and fetch the balance from the node
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<u64>` - The nonce gaps detected for this wallet
+    #[inline]
+    pub async fn refresh(&self, client: &DynProvider) -> Result<Vec<u64>, Error> {
+        let address = self.address();
+        let (gaps, balance) = tokio::try_join!(self.nonce.sync(client), async {
+            client
+                .get_balance(address)
+                .pending()
+                .await
+                .with_context(|| format!("Failed to fetch balance of {}", address))
+        })?;
+
+        *self.balance.lock().await = balance;
+
+        Ok(gaps)
+    }
+}
+
+/// A set of wallets sharing one provider
+#[derive(Debug)]
+pub struct WalletPool {
+    wallets: Vec<Arc<Wallet>>,
+    cursor: AtomicUsize,
+}
+
+impl WalletPool {
+    /// Create a pool from already parsed signers
+    ///
+    /// # Arguments
+    ///
+    /// * `signers` - The signers, the first one being the primary wallet
+    /// * `chain_id` - The chain id used to sign transactions
+    pub fn new(signers: Vec<PrivateKeySigner>, chain_id: u64) -> Result<Self, Error> {
+        if signers.is_empty() {
+            return Err(Error::msg("Wallet pool needs at least one private key"));
+        }
+
+        let wallets = signers
+            .into_iter()
+            .map(|signer| Arc::new(Wallet::new(Sender::from_signer(signer, chain_id))))
+            .collect();
+
+        Ok(Self {
+            wallets,
+            cursor: AtomicUsize::new(0),
+        })
+    }
+
+    /// Parse private keys into signers
+    pub fn parse_keys(private_keys: &[&str]) -> Result<Vec<PrivateKeySigner>, Error> {
+        private_keys
+            .iter()
+            .map(|key| key.parse::<PrivateKeySigner>().map_err(Error::from))
+            .collect()
+    }
+
+    /// Build an Alloy wallet holding every signer of the pool
+    ///
+    /// The provider filler picks the signer matching the `from` of each transaction.
+    pub fn ethereum_wallet(signers: &[PrivateKeySigner]) -> EthereumWallet {
+        let mut wallet = EthereumWallet::default();
+        for signer in signers {
+            wallet.register_signer(signer.clone());
+        }
+        if let Some(primary) = signers.first() {
+            wallet.register_default_signer(primary.clone());
+        }
+        wallet
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.wallets.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.wallets.is_empty()
+    }
+
+    /// The primary wallet
+    #[inline]
+    pub fn primary(&self) -> Arc<Wallet> {
+        Arc::clone(&self.wallets[0])
+    }
+
+    /// Get a wallet by its index in the pool
+    #[inline]
+    pub fn by_index(&self, index: usize) -> Option<Arc<Wallet>> {
+        self.wallets.get(index).cloned()
+    }
+
+    /// Get a wallet by its address
+    #[inline]
+    pub fn get(&self, address: &Address) -> Option<Arc<Wallet>> {
+        self.wallets
+            .iter()
+            .find(|wallet| wallet.address() == *address)
+            .cloned()
+    }
+
+    /// Get the next wallet in round-robin order
+    #[inline]
+    pub fn next(&self) -> Arc<Wallet> {
+        let index = self.cursor.fetch_add(1, Ordering::Relaxed) % self.wallets.len();
+        Arc::clone(&self.wallets[index])
+    }
+
+    #[inline]
+    pub fn addresses(&self) -> Vec<Address> {
+        self.wallets.iter().map(|wallet| wallet.address()).collect()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<Wallet>> {
+        self.wallets.iter()
+    }
+
+    /// Sync nonce and balance of every wallet concurrently
+    ///
+    /// # Returns
+    ///
+    /// * `Vec<(Address, Vec<u64>)>` - The wallets with nonce gaps and their gaps
+    #[inline]
+    pub async fn refresh(&self, client: &DynProvider) -> Result<Vec<(Address, Vec<u64>)>, Error> {
+        let gaps = try_join_all(self.wallets.iter().map(|wallet| wallet.refresh(client))).await?;
+        Ok(self
+            .wallets
+            .iter()
+            .map(|wallet| wallet.address())
+            .zip(gaps)
+            .filter(|(_, gaps)| !gaps.is_empty())
+            .collect())
+    }
+
+    /// Start a background task to refresh every wallet periodically
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The provider to query
+    /// * `interval` - The interval between updates
+    ///
+    /// # Returns
+    ///
+    /// * `JoinHandle` - The handle to the background task
+    #[inline]
+    pub fn update_wallets(
+        self: &Arc<Self>,
+        client: DynProvider,
+        interval: Duration,
+    ) -> JoinHandle<()> {
+        let pool = Arc::clone(self);
+        tokio::spawn(async move {
+            loop {
+                match pool.refresh(&client).await {
+                    Ok(gaps) => {
+                        for (address, gaps) in gaps {
+                            eprintln!("nonce gaps detected for {}: {:?}", address, gaps);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("failed to refresh wallets: {:?}", e);
+                    }
+                }
+                sleep(interval).await;
+            }
+        })
+    }
+}
+
+#[test]
+fn test_wallet_pool_selection() {
+    let keys = [
+        "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        "59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
+    ];
+    let pool = WalletPool::new(WalletPool::parse_keys(&keys).unwrap(), 56).unwrap();
+    let addresses = pool.addresses();
+
+    assert_eq!(pool.len(), 2);
+    assert_eq!(pool.primary().address(), addresses[0]);
+    assert_eq!(pool.next().address(), addresses[0]);
+    assert_eq!(pool.next().address(), addresses[1]);
+    assert_eq!(pool.next().address(), addresses[0]);
+    assert_eq!(pool.get(&addresses[1]).unwrap().address(), addresses[1]);
+    assert!(WalletPool::new(Vec::new(), 56).is_err());
+}
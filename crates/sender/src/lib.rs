@@ -1,22 +1,142 @@
-use alloy::signers::local::PrivateKeySigner;
+use alloy::{
+    consensus::TxEnvelope,
+    eips::Encodable2718,
+    hex,
+    network::{EthereumWallet, TransactionBuilder},
+    primitives::{Address, B256, Bytes},
+    rpc::types::TransactionRequest,
+    signers::local::PrivateKeySigner,
+};
 use anyhow::Error;
 
+/// A signed, EIP-2718 encoded transaction ready for submission
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedTransaction {
+    pub raw: Bytes,
+    pub hash: B256,
+}
+
+impl SignedTransaction {
+    /// Raw transaction hex string without 0x prefix
+    #[inline]
+    pub fn to_hex(&self) -> String {
+        hex::encode(&self.raw)
+    }
+}
+
+impl From<TxEnvelope> for SignedTransaction {
+    fn from(envelope: TxEnvelope) -> Self {
+        Self {
+            hash: *envelope.tx_hash(),
+            raw: envelope.encoded_2718().into(),
+        }
+    }
+}
+
+/// Local transaction signer bound to a single chain
 #[derive(Clone, Debug)]
 pub struct Sender {
     pub singer: PrivateKeySigner,
+    wallet: EthereumWallet,
+    chain_id: u64,
 }
 
 impl Sender {
     #[inline]
-    pub fn new(private_key: &str) -> Result<Self, Error> {
+    pub fn new(private_key: &str, chain_id: u64) -> Result<Self, Error> {
         let pk_signer: PrivateKeySigner = private_key.parse()?;
-        Ok(Self { singer: pk_signer })
+        Ok(Self::from_signer(pk_signer, chain_id))
+    }
+
+    #[inline]
+    pub fn from_signer(signer: PrivateKeySigner, chain_id: u64) -> Self {
+        Self {
+            wallet: EthereumWallet::from(signer.clone()),
+            singer: signer,
+            chain_id,
+        }
+    }
+
+    /// The address transactions are signed from
+    #[inline]
+    pub fn address(&self) -> Address {
+        self.singer.address()
+    }
+
+    /// The chain id used for EIP-155 replay protection
+    #[inline]
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// Sign a transaction request without touching the network
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction request, with nonce, gas limit and fees already set
+    ///
+    /// # Returns
+    ///
+    /// * `SignedTransaction` - The raw transaction and its hash
+    ///
+    /// # Note
+    ///
+    /// The sender address and the cached chain id are filled in when missing. Any
+    /// transaction type the request resolves to (legacy, EIP-2930, EIP-1559, EIP-4844,
+    /// EIP-7702) is supported.
+    #[inline]
+    pub async fn sign(&self, mut tx: TransactionRequest) -> Result<SignedTransaction, Error> {
+        tx.set_from(self.address());
+        if tx.chain_id.is_none() {
+            tx.set_chain_id(self.chain_id);
+        }
+
+        let envelope: TxEnvelope = tx
+            .build(&self.wallet)
+            .await
+            .map_err(|e| Error::msg(format!("Failed to sign transaction: {:?}", e)))?;
+
+        Ok(envelope.into())
     }
 }
 
 #[test]
 fn test_new() {
     let private_key = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
-    let sender = Sender::new(private_key).unwrap();
+    let sender = Sender::new(private_key, 56).unwrap();
     println!("Sender: {:?}", sender.singer.address());
 }
+
+#[cfg(test)]
+#[tokio::test]
+async fn test_sign() {
+    use alloy::{
+        consensus::{Transaction, transaction::SignerRecoverable},
+        eips::Decodable2718,
+        primitives::keccak256,
+    };
+
+    let private_key = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    let sender = Sender::new(private_key, 56).unwrap();
+
+    let legacy = TransactionRequest::default()
+        .with_to(Address::ZERO)
+        .with_nonce(0)
+        .with_gas_limit(21000)
+        .with_gas_price(1_000_000_000);
+    let eip1559 = TransactionRequest::default()
+        .with_to(Address::ZERO)
+        .with_nonce(1)
+        .with_gas_limit(21000)
+        .with_max_fee_per_gas(2_000_000_000)
+        .with_max_priority_fee_per_gas(1_000_000_000);
+
+    for tx in [legacy, eip1559] {
+        let signed = sender.sign(tx).await.unwrap();
+        assert_eq!(signed.hash, keccak256(&signed.raw));
+
+        let envelope = TxEnvelope::decode_2718(&mut signed.raw.as_ref()).unwrap();
+        assert_eq!(envelope.recover_signer().unwrap(), sender.address());
+        assert_eq!(envelope.chain_id(), Some(56));
+    }
+}
@@ -1,24 +1,137 @@
 use iceoryx2::prelude::ZeroCopySend;
 
+/// Maximum number of tokens in one `GetBatchPrices` request or `ListTokens` page
+pub const MAX_BATCH_SIZE: usize = 32;
+/// Maximum number of open subscriptions per client
+pub const MAX_SUBSCRIPTIONS: usize = 64;
+/// Responses buffered per subscription before the oldest is overwritten
+pub const SUBSCRIPTION_BUFFER_SIZE: usize = 256;
+/// Price updates buffered per subscriber of the update stream before the oldest is dropped
+pub const UPDATE_BUFFER_SIZE: usize = 1024;
+/// Prefix of the tracker's IPC service names
+pub const DEFAULT_SERVICE_PREFIX: &str = "token_price";
+
+/// Names of the tracker's IPC services, the tracker and its clients must agree on them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceNames {
+    /// Request/response service of one-off requests
+    pub query: String,
+    /// Request/response service of `Subscribe` and `Unsubscribe`
+    pub subscriptions: String,
+    /// Publish/subscribe service of `PriceTick`s
+    pub updates: String,
+    /// Event service notified after every `PriceTick`, to wake waiting subscribers
+    pub update_events: String,
+}
+
+impl ServiceNames {
+    /// `<prefix>_query`, `<prefix>_subscriptions`, `<prefix>_updates` and
+    /// `<prefix>_update_events`
+    #[inline]
+    pub fn with_prefix(prefix: &str) -> Self {
+        Self {
+            query: format!("{prefix}_query"),
+            subscriptions: format!("{prefix}_subscriptions"),
+            updates: format!("{prefix}_updates"),
+            update_events: format!("{prefix}_update_events"),
+        }
+    }
+}
+
+impl Default for ServiceNames {
+    fn default() -> Self {
+        Self::with_prefix(DEFAULT_SERVICE_PREFIX)
+    }
+}
+
 /// Request type enum
-#[derive(Debug, ZeroCopySend)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ZeroCopySend)]
 #[repr(C)]
 pub enum RequestType {
+    #[default]
     GetPrice = 0,
     RemoveToken = 1,
+    /// Start tracking `token_address`, from its Pancake pair when it already holds liquidity
+    AddToken = 2,
+    /// One response per tracked token, `MAX_BATCH_SIZE` tokens from `offset`
+    ListTokens = 3,
+    /// Pancake pair of `token_address`: pair and quote addresses, `is_token0` and reserves
+    GetPair = 4,
+    /// One response per address of `token_addresses[..token_count]`
+    GetBatchPrices = 5,
+    /// Keep the request open and receive a response on every price change of `token_address`,
+    /// sent on the `token_price_subscriptions` service
+    Subscribe = 6,
+    /// Close the subscriptions of this client to `token_address`, sent on the
+    /// `token_price_subscriptions` service
+    Unsubscribe = 7,
 }
 
 /// query price request
-#[derive(Debug, ZeroCopySend)]
+#[derive(Debug, Clone, Copy, Default, ZeroCopySend)]
 #[repr(C)]
 pub struct PriceRequest {
     pub request_type: RequestType,
     pub token_address: [u8; 20],
+    pub token_addresses: [[u8; 20]; MAX_BATCH_SIZE], // GetBatchPrices
+    pub token_count: u32,                            // GetBatchPrices
+    pub offset: u32,                                 // ListTokens
+}
+
+/// Venue that produced the latest price
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ZeroCopySend)]
+#[repr(C)]
+pub enum PriceSource {
+    #[default]
+    None = 0,
+    FourmemeCurve = 1,
+    PancakePair = 2,
+}
+
+/// Tracking status of a token
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ZeroCopySend)]
+#[repr(C)]
+pub enum TokenStatus {
+    #[default]
+    Unknown = 0,
+    /// Trading on the Fourmeme bonding curve
+    Tracked = 1,
+    /// Removed from tracking until its next trade
+    Removed = 2,
+    /// Graduated and priced from its PancakeSwap pair
+    Migrated = 3,
 }
 
 /// query price response
-#[derive(Debug, ZeroCopySend)]
+///
+/// Amounts are big-endian uint256, so no price is ever truncated; zero means unknown.
+#[derive(Debug, Clone, Copy, Default, ZeroCopySend)]
 #[repr(C)]
 pub struct PriceResponse {
-    pub wei_per_token: u128, // price
+    pub token_address: [u8; 20],
+    pub status: TokenStatus,
+    pub source: PriceSource,
+    pub wei_per_token: [u8; 32],  // price
+    pub usd_per_token: [u8; 32],  // USD price scaled by 1e18
+    pub usd_market_cap: [u8; 32], // USD market cap scaled by 1e18
+    pub block_number: u64,        // block of the latest price update
+    pub timestamp: u64,           // unix seconds of the latest price update
+    pub quote_token: [u8; 20],    // quote asset, zero for BNB
+    pub pair_address: [u8; 20],   // Pancake pair, zero while on the curve
+    pub reserve_token: [u8; 32],  // pair reserve, or tokens left on the curve
+    pub reserve_quote: [u8; 32],  // pair reserve, or funds raised by the curve
+    pub progress_bps: u16,        // curve progress, 10_000 once migrated
+    pub is_token0: bool,          // GetPair: whether the token is token0 of its pair
+}
+
+/// price update published on every price change
+#[derive(Debug, Clone, Copy, Default, ZeroCopySend)]
+#[repr(C)]
+pub struct PriceTick {
+    pub token_address: [u8; 20],
+    pub source: PriceSource,
+    pub wei_per_token: [u8; 32], // new price
+    pub usd_per_token: [u8; 32], // USD price scaled by 1e18
+    pub block_number: u64,       // block of the price change
+    pub timestamp: u64,          // unix seconds of the price change
 }